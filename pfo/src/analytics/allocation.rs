@@ -0,0 +1,182 @@
+/// A fund that can be bought by [`allocate`]
+#[derive(Clone, Debug)]
+pub struct AllocationFund {
+    pub price: f64,
+    pub weight: f64,
+    pub owned: u32,
+    pub min_amount: u32,
+}
+
+/// Number of units to buy from each fund so that holdings get as close as possible to their
+/// target weights without spending more than `budget`.
+///
+/// Targets are the weights of the portfolio value after spending the whole budget. Units are bought
+/// greedily, one step at a time, choosing the step that reduces the squared distance to targets
+/// the most, until no affordable step reduces it. A fund is either not bought or bought at least
+/// `min_amount` units.
+pub fn allocate(funds: &[AllocationFund], budget: f64) -> Vec<u32> {
+    let mut amounts = vec![0u32; funds.len()];
+    let total_weight: f64 = funds.iter().map(|f| f.weight).sum();
+    if total_weight <= 0.0 {
+        return amounts;
+    }
+
+    let current: f64 = funds.iter().map(|f| f.owned as f64 * f.price).sum();
+    let final_value = current + budget;
+    let targets: Vec<f64> = funds
+        .iter()
+        .map(|f| f.weight / total_weight * final_value)
+        .collect();
+
+    let mut spent = 0.0;
+    loop {
+        let best = funds
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.price > 0.0)
+            .filter_map(|(i, f)| {
+                let units = if amounts[i] == 0 {
+                    f.min_amount.max(1)
+                } else {
+                    1
+                };
+                let cost = units as f64 * f.price;
+                if spent + cost > budget
+                    || (budget - (spent + cost) <= budget * 1e-9
+                        && cost_after(funds, &amounts, i, units) > budget)
+                {
+                    return None;
+                }
+
+                let value = (f.owned + amounts[i]) as f64 * f.price;
+                let before = (value - targets[i]).powi(2);
+                let after = (value + cost - targets[i]).powi(2);
+
+                (after < before).then_some((i, units, before - after))
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+
+        match best {
+            Some((i, units, _)) => {
+                amounts[i] += units;
+                spent += units as f64 * funds[i].price;
+            }
+            None => break,
+        }
+    }
+
+    amounts
+}
+
+/// Cost of `amounts` with `units` more of fund `i`, summed fund by fund like callers do. Running
+/// sums can round differently, which matters only when a step just fits the budget.
+fn cost_after(funds: &[AllocationFund], amounts: &[u32], i: usize, units: u32) -> f64 {
+    funds
+        .iter()
+        .zip(amounts)
+        .enumerate()
+        .map(|(j, (f, amount))| (amount + if j == i { units } else { 0 }) as f64 * f.price)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fund(price: f64, weight: f64, owned: u32, min_amount: u32) -> AllocationFund {
+        AllocationFund {
+            price,
+            weight,
+            owned,
+            min_amount,
+        }
+    }
+
+    /// Cost of `amounts` summed fund by fund, like callers do
+    fn total_cost(funds: &[AllocationFund], amounts: &[u32]) -> f64 {
+        funds
+            .iter()
+            .zip(amounts)
+            .map(|(f, amount)| *amount as f64 * f.price)
+            .sum()
+    }
+
+    #[test]
+    fn buys_towards_target_weights() {
+        // Targets are 75 and 25, an 8th unit of the first fund would overshoot as much as it
+        // undershoots now
+        let funds = [fund(10.0, 3.0, 0, 0), fund(5.0, 1.0, 0, 0)];
+
+        assert_eq!(allocate(&funds, 100.0), [7, 5]);
+    }
+
+    #[test]
+    fn owned_units_count_towards_targets() {
+        let funds = [fund(10.0, 1.0, 10, 0), fund(10.0, 1.0, 0, 0)];
+
+        assert_eq!(allocate(&funds, 100.0), [0, 10]);
+    }
+
+    #[test]
+    fn budget_below_cheapest_price_buys_nothing() {
+        let funds = [fund(10.0, 1.0, 0, 0), fund(25.0, 1.0, 0, 0)];
+
+        assert_eq!(allocate(&funds, 9.99), [0, 0]);
+        assert_eq!(allocate(&funds, 0.0), [0, 0]);
+    }
+
+    #[test]
+    fn min_amount_is_bought_at_once_or_not_at_all() {
+        let funds = [fund(10.0, 1.0, 0, 5), fund(10.0, 1.0, 0, 0)];
+
+        // 5 units of the first fund cost more than the budget
+        assert_eq!(allocate(&funds, 40.0), [0, 2]);
+        assert_eq!(allocate(&funds, 100.0), [5, 5]);
+    }
+
+    #[test]
+    fn funds_without_weight_or_price_are_not_bought() {
+        assert_eq!(allocate(&[fund(10.0, 0.0, 0, 0)], 100.0), [0]);
+        assert_eq!(
+            allocate(&[fund(0.0, 1.0, 0, 0), fund(10.0, 1.0, 0, 0)], 100.0),
+            [0, 5]
+        );
+        assert!(allocate(&[], 100.0).is_empty());
+    }
+
+    #[test]
+    fn cost_never_exceeds_budget() {
+        let funds = [
+            fund(3.7, 1.0, 4, 0),
+            fund(12.5, 2.0, 0, 3),
+            fund(0.9, 3.0, 20, 10),
+            fund(101.0, 0.5, 1, 0),
+        ];
+
+        for i in 0..300 {
+            let budget = i as f64 * 7.3;
+            let amounts = allocate(&funds, budget);
+
+            assert!(
+                total_cost(&funds, &amounts) <= budget,
+                "{:?} costs more than {}",
+                amounts,
+                budget
+            );
+            for (f, amount) in funds.iter().zip(&amounts) {
+                assert!(*amount == 0 || *amount >= f.min_amount);
+            }
+        }
+    }
+
+    #[test]
+    fn large_budget_is_spent_on_cheap_funds() {
+        let funds: Vec<AllocationFund> =
+            (1..=10).map(|i| fund(0.01 * i as f64, 1.0, 0, 0)).collect();
+
+        let amounts = allocate(&funds, 10_000.0);
+
+        let cost = total_cost(&funds, &amounts);
+        assert!(cost <= 10_000.0 && cost > 9_999.9, "{}", cost);
+    }
+}
@@ -0,0 +1,10 @@
+mod allocation;
+mod performance;
+mod series;
+
+/// Days of a year when annualizing returns, the same for XIRR and CAGR
+const DAYS_PER_YEAR: f64 = 365.0;
+
+pub use allocation::{AllocationFund, allocate};
+pub use performance::{CashFlow, Trade, time_weighted_return, xirr};
+pub use series::PriceSeries;
@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::analytics::DAYS_PER_YEAR;
+
+const XIRR_TOLERANCE: f64 = 1e-9;
+const XIRR_MAX_ITERATIONS: usize = 100;
+
+/// Money moving in (positive) or out (negative) of the investor's pocket at a date
+#[derive(Clone, Copy, Debug)]
+pub struct CashFlow {
+    pub date: NaiveDate,
+    pub amount: f64,
+}
+
+/// Change in held units of a fund at a date, negative for sells
+#[derive(Clone, Debug)]
+pub struct Trade<'a> {
+    pub date: NaiveDate,
+    pub code: &'a str,
+    pub units: i64,
+}
+
+fn npv(flows: &[CashFlow], start: NaiveDate, rate: f64) -> f64 {
+    flows
+        .iter()
+        .map(|f| {
+            let years = (f.date - start).num_days() as f64 / DAYS_PER_YEAR;
+            f.amount / (1.0 + rate).powf(years)
+        })
+        .sum()
+}
+
+fn npv_derivative(flows: &[CashFlow], start: NaiveDate, rate: f64) -> f64 {
+    flows
+        .iter()
+        .map(|f| {
+            let years = (f.date - start).num_days() as f64 / DAYS_PER_YEAR;
+            -years * f.amount / (1.0 + rate).powf(years + 1.0)
+        })
+        .sum()
+}
+
+/// Annualized money-weighted return of irregular cash flows.
+///
+/// Returns `None` if flows do not contain both positive and negative amounts or no rate could be
+/// found. Newton's method is tried first, falling back to bisection.
+pub fn xirr(flows: &[CashFlow]) -> Option<f64> {
+    let start = flows.iter().map(|f| f.date).min()?;
+    if !flows.iter().any(|f| f.amount > 0.0) || !flows.iter().any(|f| f.amount < 0.0) {
+        return None;
+    }
+
+    let mut rate = 0.1;
+    for _ in 0..XIRR_MAX_ITERATIONS {
+        let value = npv(flows, start, rate);
+        let derivative = npv_derivative(flows, start, rate);
+        if derivative == 0.0 {
+            break;
+        }
+
+        let next = rate - value / derivative;
+        if !next.is_finite() || next <= -1.0 {
+            break;
+        }
+
+        if (next - rate).abs() < XIRR_TOLERANCE {
+            return Some(next);
+        }
+        rate = next;
+    }
+
+    let (mut low, mut high) = (-0.999_999, 1.0);
+    while npv(flows, start, low).signum() == npv(flows, start, high).signum() {
+        high *= 2.0;
+        if high > 1e6 {
+            return None;
+        }
+    }
+
+    for _ in 0..XIRR_MAX_ITERATIONS * 10 {
+        let mid = (low + high) / 2.0;
+        let value = npv(flows, start, mid);
+        if value.abs() < XIRR_TOLERANCE || (high - low) / 2.0 < XIRR_TOLERANCE {
+            return Some(mid);
+        }
+
+        if value.signum() == npv(flows, start, low).signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    None
+}
+
+/// Time-weighted return of holdings built by `trades` and valued at `end`.
+///
+/// The holding period is split at each trade date and returns of the sub-periods are chained, so
+/// the result is independent of the amount and timing of contributions. `price_at` gives the price
+/// of a fund at a date and `None` is returned if any needed price is missing.
+pub fn time_weighted_return<F>(trades: &[Trade], end: NaiveDate, price_at: F) -> Option<f64>
+where
+    F: Fn(&str, NaiveDate) -> Option<f64>,
+{
+    let value = |holdings: &HashMap<&str, i64>, date: NaiveDate| -> Option<f64> {
+        holdings
+            .iter()
+            .filter(|(_, units)| **units != 0)
+            .map(|(code, units)| Some(*units as f64 * price_at(code, date)?))
+            .sum()
+    };
+
+    let mut trades: Vec<&Trade> = trades.iter().filter(|t| t.date <= end).collect();
+    trades.sort_by_key(|t| t.date);
+
+    let mut holdings: HashMap<&str, i64> = HashMap::new();
+    let mut growth = 1.0;
+    let mut periods = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    let mut dates: Vec<NaiveDate> = trades.iter().map(|t| t.date).collect();
+    dates.push(end);
+    dates.dedup();
+
+    for date in dates {
+        if let Some(previous) = previous {
+            let begin_value = value(&holdings, previous)?;
+            if begin_value > 0.0 {
+                growth *= value(&holdings, date)? / begin_value;
+                periods += 1;
+            }
+        }
+
+        for trade in trades.iter().filter(|t| t.date == date) {
+            *holdings.entry(trade.code).or_default() += trade.units;
+        }
+        previous = Some(date);
+    }
+
+    (periods > 0).then_some(growth - 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Datelike;
+
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn flow(date: NaiveDate, amount: f64) -> CashFlow {
+        CashFlow { date, amount }
+    }
+
+    #[test]
+    fn xirr_of_one_year_gain() {
+        let flows = [
+            flow(date(2023, 1, 1), -1000.0),
+            flow(date(2024, 1, 1), 1100.0),
+        ];
+
+        let rate = xirr(&flows).unwrap();
+        assert!((rate - 0.1).abs() < 1e-9, "{}", rate);
+    }
+
+    #[test]
+    fn xirr_falls_back_to_bisection() {
+        // Newton's first step from 10% lands below -100%, the rate is -99%
+        let flows = [flow(date(2023, 1, 1), -100.0), flow(date(2024, 1, 1), 1.0)];
+
+        let rate = xirr(&flows).unwrap();
+        assert!((rate + 0.99).abs() < 1e-6, "{}", rate);
+    }
+
+    #[test]
+    fn xirr_needs_flows_of_both_signs() {
+        let outflows = [
+            flow(date(2023, 1, 1), -100.0),
+            flow(date(2024, 1, 1), -50.0),
+        ];
+        let inflows = [flow(date(2023, 1, 1), 100.0), flow(date(2024, 1, 1), 50.0)];
+
+        assert_eq!(xirr(&outflows), None);
+        assert_eq!(xirr(&inflows), None);
+        assert_eq!(xirr(&[]), None);
+    }
+
+    #[test]
+    fn time_weighted_return_ignores_contributions() {
+        let prices = [
+            (date(2024, 1, 1), 10.0),
+            (date(2024, 2, 1), 11.0),
+            (date(2024, 3, 1), 12.1),
+        ];
+        let price_at =
+            |_: &str, date: NaiveDate| prices.iter().find(|(d, _)| *d == date).map(|(_, p)| *p);
+        let trades = [
+            Trade {
+                date: date(2024, 1, 1),
+                code: "AAA",
+                units: 10,
+            },
+            Trade {
+                date: date(2024, 2, 1),
+                code: "AAA",
+                units: 90,
+            },
+        ];
+
+        let twr = time_weighted_return(&trades, date(2024, 3, 1), price_at).unwrap();
+        assert!((twr - 0.21).abs() < 1e-9, "{}", twr);
+    }
+
+    #[test]
+    fn time_weighted_return_needs_a_period() {
+        let price_at = |_: &str, _: NaiveDate| Some(10.0);
+        let trades = [Trade {
+            date: date(2024, 1, 1),
+            code: "AAA",
+            units: 10,
+        }];
+
+        assert_eq!(time_weighted_return(&[], date(2024, 1, 1), price_at), None);
+        assert_eq!(
+            time_weighted_return(&trades, date(2024, 1, 1), price_at),
+            None
+        );
+    }
+
+    #[test]
+    fn time_weighted_return_needs_all_prices() {
+        let price_at = |_: &str, date: NaiveDate| (date.month() == 1).then_some(10.0);
+        let trades = [Trade {
+            date: date(2024, 1, 1),
+            code: "AAA",
+            units: 10,
+        }];
+
+        assert_eq!(
+            time_weighted_return(&trades, date(2024, 2, 1), price_at),
+            None
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use chrono::NaiveDate;
+
+use crate::analytics::DAYS_PER_YEAR;
+
+/// Price observations of a single fund, sorted by date
+pub struct PriceSeries {
+    points: Vec<(NaiveDate, f64)>,
+}
+
+impl PriceSeries {
+    /// Series of `points`, leaving out those without a positive price since returns can not be
+    /// computed from them
+    pub fn new(mut points: Vec<(NaiveDate, f64)>) -> Self {
+        points.retain(|(_, price)| *price > 0.0);
+        points.sort_by_key(|(date, _)| *date);
+        points.dedup_by_key(|(date, _)| *date);
+
+        Self { points }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn first(&self) -> Option<(NaiveDate, f64)> {
+        self.points.first().copied()
+    }
+
+    pub fn last(&self) -> Option<(NaiveDate, f64)> {
+        self.points.last().copied()
+    }
+
+    /// Latest known price on or before `date`
+    pub fn price_at(&self, date: NaiveDate) -> Option<f64> {
+        self.points
+            .iter()
+            .rev()
+            .find(|(d, _)| *d <= date)
+            .map(|(_, price)| *price)
+    }
+
+    fn years(&self) -> Option<f64> {
+        let (first, _) = self.first()?;
+        let (last, _) = self.last()?;
+        let days = (last - first).num_days();
+
+        (days > 0).then(|| days as f64 / DAYS_PER_YEAR)
+    }
+
+    /// Returns between consecutive observations
+    pub fn period_returns(&self) -> Vec<f64> {
+        self.points
+            .windows(2)
+            .map(|w| w[1].1 / w[0].1 - 1.0)
+            .collect()
+    }
+
+    /// Average number of observations in a year, used to annualize period statistics
+    pub fn periods_per_year(&self) -> Option<f64> {
+        let years = self.years()?;
+        Some((self.len() - 1) as f64 / years)
+    }
+
+    pub fn total_return(&self) -> Option<f64> {
+        let (_, first) = self.first()?;
+        let (_, last) = self.last()?;
+
+        (self.len() > 1).then(|| last / first - 1.0)
+    }
+
+    /// Return of the last `days` days, measured from the latest observation on or before the
+    /// start of the window
+    pub fn window_return(&self, days: u32) -> Option<f64> {
+        let (last_date, last_price) = self.last()?;
+        let start = last_date - chrono::Duration::days(days.into());
+
+        self.points
+            .iter()
+            .rev()
+            .find(|(date, _)| *date <= start)
+            .map(|(_, price)| last_price / price - 1.0)
+    }
+
+    /// Compound annual growth rate
+    pub fn cagr(&self) -> Option<f64> {
+        let total = self.total_return()?;
+        let years = self.years()?;
+
+        Some((1.0 + total).powf(1.0 / years) - 1.0)
+    }
+
+    /// Annualized standard deviation of period returns
+    pub fn volatility(&self) -> Option<f64> {
+        let returns = self.period_returns();
+        let std_dev = std_dev(&returns)?;
+
+        Some(std_dev * self.periods_per_year()?.sqrt())
+    }
+
+    /// Largest peak to trough decline, as a positive fraction of the peak
+    pub fn max_drawdown(&self) -> Option<f64> {
+        let mut peak = self.first()?.1;
+        let mut drawdown: f64 = 0.0;
+
+        for (_, price) in &self.points {
+            peak = peak.max(*price);
+            drawdown = drawdown.max((peak - price) / peak);
+        }
+
+        Some(drawdown)
+    }
+
+    /// Annualized Sharpe ratio for an annual `risk_free_rate`
+    pub fn sharpe_ratio(&self, risk_free_rate: f64) -> Option<f64> {
+        let excess = self.excess_returns(risk_free_rate)?;
+        let std_dev = std_dev(&excess)?;
+        if std_dev == 0.0 {
+            return None;
+        }
+
+        Some(mean(&excess)? / std_dev * self.periods_per_year()?.sqrt())
+    }
+
+    /// Annualized Sortino ratio for an annual `risk_free_rate`, only penalizing returns below it
+    pub fn sortino_ratio(&self, risk_free_rate: f64) -> Option<f64> {
+        let excess = self.excess_returns(risk_free_rate)?;
+        let downside =
+            (excess.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / excess.len() as f64).sqrt();
+        if downside == 0.0 {
+            return None;
+        }
+
+        Some(mean(&excess)? / downside * self.periods_per_year()?.sqrt())
+    }
+
+    fn excess_returns(&self, risk_free_rate: f64) -> Option<Vec<f64>> {
+        let period_rate = (1.0 + risk_free_rate).powf(1.0 / self.periods_per_year()?) - 1.0;
+        let excess: Vec<f64> = self
+            .period_returns()
+            .iter()
+            .map(|r| r - period_rate)
+            .collect();
+
+        (!excess.is_empty()).then_some(excess)
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Sample standard deviation
+fn std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+
+    let mean = mean(values)?;
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+
+    Some(variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.unwrap();
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    /// Up 10%, then down 10%, then up 10% again over one and a half years
+    fn series() -> PriceSeries {
+        PriceSeries::new(vec![
+            (date(2025, 1, 1), 99.0),
+            (date(2024, 1, 1), 100.0),
+            (date(2025, 7, 1), 108.9),
+            (date(2024, 7, 1), 110.0),
+        ])
+    }
+
+    #[test]
+    fn points_are_sorted_and_deduplicated() {
+        let series = PriceSeries::new(vec![
+            (date(2024, 2, 1), 11.0),
+            (date(2024, 1, 1), 10.0),
+            (date(2024, 2, 1), 12.0),
+        ]);
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series.first(), Some((date(2024, 1, 1), 10.0)));
+        assert_eq!(series.last(), Some((date(2024, 2, 1), 11.0)));
+    }
+
+    #[test]
+    fn points_without_positive_price_are_left_out() {
+        let series = PriceSeries::new(vec![
+            (date(2024, 1, 1), 0.0),
+            (date(2024, 2, 1), 10.0),
+            (date(2024, 3, 1), 0.0),
+            (date(2024, 4, 1), 11.0),
+            (date(2024, 5, 1), f64::NAN),
+        ]);
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series.first(), Some((date(2024, 2, 1), 10.0)));
+        assert_close(series.period_returns().first().copied(), 0.1);
+        assert_close(series.total_return(), 0.1);
+        assert_close(series.max_drawdown(), 0.0);
+    }
+
+    #[test]
+    fn price_at_takes_latest_price_on_or_before_date() {
+        let series = series();
+
+        assert_eq!(series.price_at(date(2023, 12, 31)), None);
+        assert_eq!(series.price_at(date(2024, 1, 1)), Some(100.0));
+        assert_eq!(series.price_at(date(2024, 12, 31)), Some(110.0));
+        assert_eq!(series.price_at(date(2030, 1, 1)), Some(108.9));
+    }
+
+    #[test]
+    fn returns() {
+        let series = series();
+
+        let returns = series.period_returns();
+        assert_eq!(returns.len(), 3);
+        for (actual, expected) in returns.into_iter().zip([0.1, -0.1, 0.1]) {
+            assert_close(Some(actual), expected);
+        }
+        assert_close(series.total_return(), 0.089);
+        // 547 days from the first to the last price
+        assert_close(series.cagr(), 1.089_f64.powf(DAYS_PER_YEAR / 547.0) - 1.0);
+    }
+
+    #[test]
+    fn window_return_starts_at_latest_price_before_window() {
+        let series = series();
+
+        assert_close(series.window_return(30), 0.1);
+        assert_close(series.window_return(181), 0.1);
+        assert_close(series.window_return(182), 108.9 / 110.0 - 1.0);
+        assert_eq!(series.window_return(600), None);
+    }
+
+    #[test]
+    fn risk() {
+        let series = series();
+        let periods_per_year = 3.0 / (547.0 / DAYS_PER_YEAR);
+        // Mean of period returns is 1/30, their sample deviation sqrt(0.04 / 3)
+        let std_dev = (0.04_f64 / 3.0).sqrt();
+
+        assert_close(series.periods_per_year(), periods_per_year);
+        assert_close(series.volatility(), std_dev * periods_per_year.sqrt());
+        assert_close(series.max_drawdown(), 0.1);
+        assert_close(
+            series.sharpe_ratio(0.0),
+            1.0 / 30.0 / std_dev * periods_per_year.sqrt(),
+        );
+        // Only the 10% fall counts as downside
+        assert_close(
+            series.sortino_ratio(0.0),
+            1.0 / 30.0 / (0.01_f64 / 3.0).sqrt() * periods_per_year.sqrt(),
+        );
+    }
+
+    #[test]
+    fn empty_series() {
+        let series = PriceSeries::new(vec![]);
+
+        assert_eq!(series.len(), 0);
+        assert_eq!(series.price_at(date(2024, 1, 1)), None);
+        assert!(series.period_returns().is_empty());
+        assert_eq!(series.periods_per_year(), None);
+        assert_eq!(series.total_return(), None);
+        assert_eq!(series.window_return(30), None);
+        assert_eq!(series.cagr(), None);
+        assert_eq!(series.volatility(), None);
+        assert_eq!(series.max_drawdown(), None);
+        assert_eq!(series.sharpe_ratio(0.0), None);
+        assert_eq!(series.sortino_ratio(0.0), None);
+    }
+
+    #[test]
+    fn single_point_series() {
+        let series = PriceSeries::new(vec![(date(2024, 1, 1), 100.0)]);
+
+        assert!(series.period_returns().is_empty());
+        assert_eq!(series.periods_per_year(), None);
+        assert_eq!(series.total_return(), None);
+        assert_eq!(series.window_return(0), Some(0.0));
+        assert_eq!(series.window_return(30), None);
+        assert_eq!(series.cagr(), None);
+        assert_eq!(series.volatility(), None);
+        assert_eq!(series.max_drawdown(), Some(0.0));
+        assert_eq!(series.sharpe_ratio(0.0), None);
+        assert_eq!(series.sortino_ratio(0.0), None);
+    }
+}
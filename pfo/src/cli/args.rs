@@ -1,13 +1,17 @@
 use std::io;
+use std::path::PathBuf;
 
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{Shell, generate};
 
 use crate::cli::fund::FundCommand;
 use crate::cli::portfolio::PortfolioCommand;
+use crate::cli::sync::SyncArgs;
+use crate::cli::update::UpdateOptions;
+use crate::error::ErrorFormat;
 
 #[derive(Parser)]
-#[command(name = "pfo")]
+#[command(name = "pfo", after_long_help = crate::error::EXIT_CODES)]
 pub struct Args {
     #[command(subcommand, help = "Subcommand")]
     pub command: Commands,
@@ -17,6 +21,78 @@ pub struct Args {
 
     #[arg(short, long, global = true, help = "Server port")]
     pub port: Option<u16>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        help = "Time to wait for connecting to server. Defaults to 10"
+    )]
+    pub connect_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        help = "Time to wait for each read of a response. Defaults to 30"
+    )]
+    pub read_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "COUNT",
+        help = "Times to retry GET requests after connection errors or 429, 502, 503 and 504 responses, with exponential backoff. Defaults to 3"
+    )]
+    pub retries: Option<u32>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "COUNT",
+        help = "Most requests sent at the same time by commands fetching many portfolios or budgets. Defaults to 8"
+    )]
+    pub max_in_flight: Option<usize>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PER_SECOND",
+        help = "Most requests started each second by commands fetching many portfolios or budgets. Unlimited by default"
+    )]
+    pub rate_limit: Option<u32>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Local database file. Defaults to pfo/pfo.db under user data directory"
+    )]
+    pub database: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Print portfolio updates as JSON with a diff against current state instead of sending them"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Do not ask for confirmation before updating a portfolio"
+    )]
+    pub yes: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t,
+        help = "Format of errors printed on stderr"
+    )]
+    pub format: ErrorFormat,
 }
 
 #[derive(Subcommand)]
@@ -33,10 +109,54 @@ pub enum Commands {
         command: FundCommand,
     },
 
+    #[command(
+        name = "sync",
+        about = "Archive fund informations and price stats in local database"
+    )]
+    Sync {
+        #[command(flatten)]
+        args: SyncArgs,
+    },
+
+    #[command(
+        name = "tui",
+        about = "Browse portfolios, holdings, price stats and predictions in a full-screen dashboard"
+    )]
+    Tui,
+
+    #[command(
+        name = "shell",
+        about = "Run commands interactively with line editing, history and completion"
+    )]
+    Shell,
+
+    #[command(
+        name = "manpages",
+        about = "Write man pages of pfo and each of its subcommands to a directory"
+    )]
+    Manpages {
+        #[arg(
+            value_name = "DIR",
+            help = "Directory for man pages. Created if missing"
+        )]
+        dir: PathBuf,
+    },
+
+    #[command(
+        name = "docs",
+        about = "Print reference of all commands with their arguments, table columns and sort keys"
+    )]
+    Docs {
+        #[arg(long, help = "Print reference as markdown instead of help texts")]
+        markdown: bool,
+    },
+
     #[command(
         name = "completions",
         visible_alias = "comp",
-        about = "Print shell completions"
+        about = "Print shell completions",
+        long_about = "Print static shell completions. For completion of portfolio ids, fund codes and sort \
+            columns fetched from the server, source the output of `COMPLETE=<SHELL> pfo` instead"
     )]
     Completions {
         #[arg(short, long)]
@@ -45,10 +165,30 @@ pub enum Commands {
 }
 
 impl Commands {
-    pub async fn handle(self, client: crate::client::PfoClient) -> anyhow::Result<()> {
+    pub async fn handle(
+        self,
+        client: pfo_client::PfoClient,
+        database: Option<PathBuf>,
+        options: UpdateOptions,
+        format: ErrorFormat,
+    ) -> anyhow::Result<()> {
         match self {
-            Commands::Portfolio { command } => command.handle(client).await,
-            Commands::Fund { command } => command.handle(client).await,
+            Commands::Portfolio { command } => command.handle(client, database, options).await,
+            Commands::Fund { command } => command.handle(client, database).await,
+            Commands::Sync { args } => args.handle(client, database).await,
+            Commands::Tui => crate::tui::run(client, database, options).await,
+            Commands::Shell => {
+                crate::cli::shell::Shell::new(client, database, options, format)
+                    .await?
+                    .run()
+                    .await
+            }
+            Commands::Manpages { dir } => {
+                let count = crate::cli::docs::write_manpages(&dir)?;
+                println!("Wrote {} man pages to {}", count, dir.display());
+                Ok(())
+            }
+            Commands::Docs { markdown } => crate::cli::docs::print_reference(markdown),
             Commands::Completions { generator } => {
                 let mut cmd = Args::command();
                 let bin_name = cmd.get_name().to_string();
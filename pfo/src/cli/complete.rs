@@ -0,0 +1,179 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use clap::{Arg, Command, CommandFactory, ValueEnum};
+use clap_complete::engine::{ArgValueCandidates, CompletionCandidate};
+use pfo_client::{FundFilter, PfoClient};
+use pfo_core::output::ColumnEnumSorted;
+use pfo_core::sort::SortDirection;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::cli::Args;
+
+/// How long values fetched from the server are reused for completions
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Host and port given on the command line being completed, or their defaults
+fn server() -> (String, u16) {
+    let (mut host, mut port) = ("localhost".to_string(), 8080);
+
+    let args: Vec<String> = std::env::args().collect();
+    for (i, arg) in args.iter().enumerate() {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (arg.as_str(), args.get(i + 1).map(String::as_str)),
+        };
+        match (name, value) {
+            ("-H" | "--host", Some(value)) => host = value.to_string(),
+            ("-p" | "--port", Some(value)) => port = value.parse().unwrap_or(port),
+            _ => {}
+        }
+    }
+
+    (host, port)
+}
+
+fn cache_path(host: &str, port: u16, name: &str) -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| {
+        dir.join("pfo")
+            .join("completions")
+            .join(format!("{}_{}_{}.json", host, port, name))
+    })
+}
+
+fn read_cache<T: DeserializeOwned>(path: &PathBuf) -> Option<T> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    if SystemTime::now().duration_since(modified).ok()? > CACHE_TTL {
+        return None;
+    }
+
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+fn write_cache<T: Serialize>(path: &PathBuf, values: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, serde_json::to_vec(values)?)?;
+    Ok(())
+}
+
+/// Pairs of value and description named `name`, from the cache if it is fresh, otherwise from the
+/// server. Completions stay silent, so errors only result in no candidates.
+fn cached<F, Fut>(name: &str, fetch: F) -> Vec<(String, String)>
+where
+    F: FnOnce(PfoClient) -> Fut,
+    Fut: Future<Output = Result<Vec<(String, String)>>>,
+{
+    let (host, port) = server();
+    let path = cache_path(&host, port, name);
+    if let Some(values) = path.as_ref().and_then(read_cache) {
+        return values;
+    }
+
+    let values = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to start runtime")
+        .and_then(|runtime| {
+            // Completions are interactive, so they rather give no candidates than wait for retries
+            let client = PfoClient::builder()
+                .host(host)
+                .port(port)
+                .connect_timeout(Duration::from_secs(2))
+                .retries(0)
+                .build()?;
+            runtime.block_on(fetch(client))
+        });
+
+    match values {
+        Ok(values) => {
+            if let Some(path) = &path {
+                let _ = write_cache(path, &values);
+            }
+            values
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+fn candidates(values: Vec<(String, String)>) -> Vec<CompletionCandidate> {
+    values
+        .into_iter()
+        .map(|(value, help)| CompletionCandidate::new(value).help(Some(help.into())))
+        .collect()
+}
+
+fn portfolios() -> Vec<CompletionCandidate> {
+    candidates(cached("portfolios", |client| async move {
+        Ok(client
+            .list_portfolios()
+            .await?
+            .into_iter()
+            .map(|p| (p.id.to_string(), p.name))
+            .collect())
+    }))
+}
+
+fn funds() -> Vec<CompletionCandidate> {
+    candidates(cached("funds", |client| async move {
+        Ok(client
+            .get_funds(FundFilter::default(), None)
+            .await?
+            .into_iter()
+            .map(|f| (f.code, f.title))
+            .collect())
+    }))
+}
+
+/// Candidates for `--sort`, column names first and then directions after a space
+pub fn sort<T: ColumnEnumSorted>(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return Vec::new();
+    };
+
+    match current.split_once(' ') {
+        Some((by, direction)) => SortDirection::value_variants()
+            .iter()
+            .filter_map(|d| d.to_possible_value())
+            .filter(|d| d.get_name().starts_with(direction))
+            .map(|d| CompletionCandidate::new(format!("{} {}", by, d.get_name())))
+            .collect(),
+        None => T::value_variants()
+            .iter()
+            .filter_map(|c| c.to_possible_value())
+            .filter(|c| c.get_name().starts_with(current))
+            .map(|c| CompletionCandidate::new(c.get_name()))
+            .collect(),
+    }
+}
+
+fn add_completers(arg: Arg) -> Arg {
+    let value_name = arg
+        .get_value_names()
+        .and_then(|names| names.first())
+        .map(|name| name.to_string());
+
+    match value_name.as_deref() {
+        Some("PORTFOLIO_ID") => arg.add(ArgValueCandidates::new(portfolios)),
+        Some("FUND_CODE" | "FUND_CODES") => arg.add(ArgValueCandidates::new(funds)),
+        _ => arg,
+    }
+}
+
+fn with_completers(command: Command) -> Command {
+    command
+        .mut_args(add_completers)
+        .mut_subcommands(with_completers)
+}
+
+/// [`Args`] command with dynamic completion of portfolio ids and fund codes
+pub fn completion_command() -> Command {
+    with_completers(Args::command())
+}
@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use clap::Args;
+use pfo_client::{FundFilter, PfoClient};
+use pfo_core::output::{Table, TableArgs};
+use pfo_core::parse_naive_date;
+use uuid::Uuid;
+
+use crate::portfolio::{PortfolioFundDiff, PortfolioFundDiffColumn, PortfolioFundPrice};
+use crate::store::Store;
+
+/// State of a portfolio to compare, either a saved snapshot or the latest snapshot saved on or
+/// before a date
+#[derive(Clone, Debug)]
+pub enum Point {
+    Snapshot(u32),
+    Date(NaiveDate),
+}
+
+impl FromStr for Point {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(id) = s.parse() {
+            return Ok(Point::Snapshot(id));
+        }
+
+        parse_naive_date(s)
+            .map(Point::Date)
+            .map_err(|_| format!("Expected a snapshot id or a date: {}", s))
+    }
+}
+
+impl Point {
+    /// Saved funds of the snapshot. Server keeps only current holdings, so funds of a date come
+    /// from the latest snapshot saved on or before it, revalued at fund prices of the date.
+    async fn fund_prices(
+        &self,
+        client: &PfoClient,
+        store: &Store,
+        id: Uuid,
+    ) -> Result<Vec<PortfolioFundPrice>> {
+        match self {
+            Point::Snapshot(snapshot_id) => store.get_snapshot_funds(id, *snapshot_id),
+            Point::Date(date) => {
+                let snapshot_id = store.get_snapshot_on(id, *date)?;
+                let mut funds = store.get_snapshot_funds(id, snapshot_id)?;
+
+                let fund_filter = FundFilter {
+                    date: Some(*date),
+                    codes: funds.iter().map(|f| f.code.clone()).collect(),
+                };
+                let prices = client.get_funds(fund_filter, None).await?;
+                for fund in &mut funds {
+                    match prices.iter().find(|p| p.code == fund.code) {
+                        Some(price) => {
+                            fund.date = price.date;
+                            fund.price = price.price;
+                        }
+                        None => log::warn!(
+                            "No price of {} on {}, using price saved in snapshot {}",
+                            fund.code,
+                            date,
+                            snapshot_id
+                        ),
+                    }
+                }
+
+                Ok(funds)
+            }
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct DiffArgs {
+    #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+    id: Uuid,
+
+    #[arg(
+        short,
+        long,
+        value_name = "SNAPSHOT|DATE",
+        help = "Snapshot id or date to compare from. A date uses holdings of the latest snapshot saved on or before it, valued at fund prices of the date"
+    )]
+    from: Point,
+
+    #[arg(
+        short,
+        long,
+        value_name = "SNAPSHOT|DATE",
+        help = "Snapshot id or date to compare to, like --from. Latest state known by server is used if omitted"
+    )]
+    to: Option<Point>,
+
+    #[command(flatten)]
+    output: TableArgs<PortfolioFundDiffColumn>,
+}
+
+impl DiffArgs {
+    pub async fn handle(self, client: PfoClient, database: Option<PathBuf>) -> Result<()> {
+        let store = Store::open(database.as_deref())?;
+
+        let from = self.from.fund_prices(&client, &store, self.id).await?;
+        let to = match &self.to {
+            Some(to) => to.fund_prices(&client, &store, self.id).await?,
+            None => {
+                client
+                    .get_portfolio_fund_prices(self.id, None, None)
+                    .await?
+            }
+        };
+
+        let diffs = PortfolioFundDiff::between(&from, &to);
+        PortfolioFundDiff::print_table(&diffs, self.output);
+
+        let from_value: f64 = diffs.iter().map(|d| d.from_value).sum();
+        let to_value: f64 = diffs.iter().map(|d| d.to_value).sum();
+        let from_spent: f64 = from.iter().map(|p| p.money_spent).sum();
+        let to_spent: f64 = to.iter().map(|p| p.money_spent).sum();
+        println!("Value: {:.6} -> {:.6}", from_value, to_value);
+        println!("Money spent: {:.6} -> {:.6}", from_spent, to_spent);
+
+        Ok(())
+    }
+}
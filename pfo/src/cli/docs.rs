@@ -0,0 +1,309 @@
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::{Arg, Command, CommandFactory};
+use clap_mangen::Man;
+use pfo_core::output::ColumnsDoc;
+use pfo_core::sort::SortDoc;
+use roff::{Roff, bold, roman};
+
+use crate::cli::Args;
+
+/// [`Args`] command built so subcommands know their full names and inherit global arguments
+fn built_command() -> Command {
+    let mut command = Args::command().disable_help_subcommand(true);
+    command.build();
+    command
+}
+
+/// Command and all of its visible subcommands, depth first with parents before their children
+fn walk(command: &Command) -> Vec<&Command> {
+    let mut commands = vec![command];
+    for sub in command.get_subcommands().filter(|c| !c.is_hide_set()) {
+        commands.extend(walk(sub));
+    }
+    commands
+}
+
+fn full_name(command: &Command) -> &str {
+    command.get_bin_name().unwrap_or(command.get_name())
+}
+
+/// Arguments documented for `command`. Global arguments are only documented for the top command.
+fn arguments(command: &Command, is_top: bool) -> impl Iterator<Item = &Arg> {
+    command
+        .get_arguments()
+        .filter(move |a| !a.is_hide_set() && a.get_id() != "help" && (is_top || !a.is_global_set()))
+}
+
+fn roff_sections(command: &Command) -> Roff {
+    let mut roff = Roff::new();
+
+    for arg in arguments(command, true) {
+        let flag = arg.get_long().unwrap_or(arg.get_id().as_str());
+
+        if let Some(ColumnsDoc(columns)) = arg.get::<ColumnsDoc>() {
+            roff.control("SH", ["COLUMNS"]);
+            roff.text([roman(format!(
+                "Columns selected by --{}. Default ones are printed when it is omitted.",
+                flag
+            ))]);
+            for column in columns {
+                roff.control("TP", []);
+                roff.text([bold(&column.name)]);
+                roff.text([roman(if column.is_default {
+                    format!("{} (default)", column.header)
+                } else {
+                    column.header.clone()
+                })]);
+            }
+        }
+
+        if let Some(sort) = arg.get::<SortDoc>() {
+            roff.control("SH", ["SORT KEYS"]);
+            roff.text([roman(format!(
+                "Keys accepted by --{}, each optionally followed by a direction.",
+                flag
+            ))]);
+            roff.control("TP", []);
+            roff.text([bold("Keys")]);
+            roff.text([roman(sort.keys.join(", "))]);
+            roff.control("TP", []);
+            roff.text([bold("Directions")]);
+            roff.text([roman(sort.directions.join(", "))]);
+        }
+    }
+
+    roff
+}
+
+/// Write a man page for `pfo` and each of its subcommands to `dir`, returning how many were written
+pub fn write_manpages(dir: &Path) -> Result<usize> {
+    fs::create_dir_all(dir).context(format!("Failed to create directory {}", dir.display()))?;
+
+    let command = built_command();
+    let commands = walk(&command);
+    for command in &commands {
+        let man = Man::new((*command).clone());
+        let path = dir.join(man.get_filename());
+
+        let mut file =
+            File::create(&path).context(format!("Failed to create {}", path.display()))?;
+        man.render(&mut file)
+            .and_then(|_| roff_sections(command).to_writer(&mut file))
+            .context(format!("Failed to write man page {}", path.display()))?;
+    }
+
+    Ok(commands.len())
+}
+
+fn escape_cell(s: &str) -> String {
+    s.trim()
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('|', "\\|")
+        .replace('\n', "<br>")
+}
+
+fn argument_name(arg: &Arg) -> String {
+    let value = arg
+        .get_value_names()
+        .map(|names| {
+            names
+                .iter()
+                .map(|name| format!("<{}>", name))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_else(|| format!("<{}>", arg.get_id().as_str().to_uppercase()));
+
+    if arg.is_positional() {
+        return value;
+    }
+
+    let mut name = match (arg.get_short(), arg.get_long()) {
+        (Some(short), Some(long)) => format!("-{}, --{}", short, long),
+        (Some(short), None) => format!("-{}", short),
+        (None, Some(long)) => format!("--{}", long),
+        (None, None) => String::new(),
+    };
+    if arg.get_action().takes_values() {
+        name.push(' ');
+        name.push_str(&value);
+    }
+    name
+}
+
+fn argument_help(arg: &Arg) -> String {
+    let mut help = arg
+        .get_long_help()
+        .or(arg.get_help())
+        .map(|h| h.to_string())
+        .unwrap_or_default();
+
+    let possible: Vec<String> = arg
+        .get_possible_values()
+        .iter()
+        .filter(|v| !v.is_hide_set())
+        .map(|v| v.get_name().to_string())
+        .collect();
+    if !possible.is_empty() && arg.get::<ColumnsDoc>().is_none() {
+        help.push_str(&format!("\nPossible values: {}", possible.join(", ")));
+    }
+
+    let defaults: Vec<_> = arg
+        .get_default_values()
+        .iter()
+        .map(|v| v.to_string_lossy())
+        .collect();
+    if !defaults.is_empty() && arg.get_action().takes_values() {
+        help.push_str(&format!("\nDefault: {}", defaults.join(", ")));
+    }
+
+    help
+}
+
+fn markdown_command(out: &mut String, command: &Command, is_top: bool) -> std::fmt::Result {
+    writeln!(out, "## `{}`\n", full_name(command))?;
+
+    if let Some(about) = command.get_long_about().or(command.get_about()) {
+        writeln!(out, "{}\n", about)?;
+    }
+
+    let usage = command.clone().render_usage().to_string();
+    let usage = usage.trim_start_matches("Usage:").trim();
+    writeln!(out, "```text\n{}\n```\n", usage)?;
+
+    if let Some(after) = command.get_after_long_help() {
+        writeln!(out, "```text\n{}\n```\n", after)?;
+    }
+
+    let subcommands: Vec<&Command> = command
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set())
+        .collect();
+    if !subcommands.is_empty() {
+        writeln!(out, "| Subcommand | Description |\n|---|---|")?;
+        for sub in subcommands {
+            let about = sub.get_about().map(|a| a.to_string()).unwrap_or_default();
+            writeln!(out, "| `{}` | {} |", sub.get_name(), escape_cell(&about))?;
+        }
+        writeln!(out)?;
+    }
+
+    let args: Vec<&Arg> = arguments(command, is_top).collect();
+    if !args.is_empty() {
+        writeln!(out, "| Argument | Description |\n|---|---|")?;
+        for arg in &args {
+            writeln!(
+                out,
+                "| `{}` | {} |",
+                argument_name(arg),
+                escape_cell(&argument_help(arg))
+            )?;
+        }
+        writeln!(out)?;
+    }
+
+    for arg in &args {
+        let flag = arg.get_long().unwrap_or(arg.get_id().as_str());
+
+        if let Some(ColumnsDoc(columns)) = arg.get::<ColumnsDoc>() {
+            writeln!(out, "Columns of `--{}`:\n", flag)?;
+            writeln!(out, "| Column | Header | Default |\n|---|---|---|")?;
+            for column in columns {
+                writeln!(
+                    out,
+                    "| `{}` | {} | {} |",
+                    column.name,
+                    column.header,
+                    if column.is_default { "yes" } else { "no" }
+                )?;
+            }
+            writeln!(out)?;
+        }
+
+        if let Some(sort) = arg.get::<SortDoc>() {
+            let code = |values: &[String]| {
+                values
+                    .iter()
+                    .map(|v| format!("`{}`", v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            writeln!(
+                out,
+                "Sort keys of `--{}`: {}. Directions: {}.\n",
+                flag,
+                code(&sort.keys),
+                code(&sort.directions)
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn text_tables(out: &mut String, command: &Command) -> std::fmt::Result {
+    for arg in arguments(command, true) {
+        let flag = arg.get_long().unwrap_or(arg.get_id().as_str());
+
+        if let Some(ColumnsDoc(columns)) = arg.get::<ColumnsDoc>() {
+            let width = columns
+                .iter()
+                .map(|c| c.name.len())
+                .max()
+                .unwrap_or_default();
+            writeln!(out, "Columns of --{} (* printed by default):", flag)?;
+            for column in columns {
+                let default = if column.is_default { " *" } else { "" };
+                writeln!(
+                    out,
+                    "  {:width$}  {}{}",
+                    column.name, column.header, default
+                )?;
+            }
+            writeln!(out)?;
+        }
+
+        if let Some(sort) = arg.get::<SortDoc>() {
+            writeln!(out, "Sort keys of --{}: {}", flag, sort.keys.join(", "))?;
+            writeln!(out, "Sort directions: {}\n", sort.directions.join(", "))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reference of all commands, as markdown or as their help texts
+pub fn reference(markdown: bool) -> Result<String> {
+    let command = built_command();
+    let mut out = String::new();
+
+    if markdown {
+        writeln!(out, "# `{}` command line reference\n", command.get_name())?;
+        for (i, sub) in walk(&command).into_iter().enumerate() {
+            markdown_command(&mut out, sub, i == 0)?;
+        }
+    } else {
+        for sub in walk(&command) {
+            let name = full_name(sub);
+            writeln!(out, "{}\n{}\n", name, "=".repeat(name.len()))?;
+            writeln!(out, "{}", sub.clone().render_long_help())?;
+            text_tables(&mut out, sub)?;
+        }
+    }
+
+    Ok(out)
+}
+
+/// Print [`reference`] to stdout
+pub fn print_reference(markdown: bool) -> Result<()> {
+    let reference = reference(markdown)?;
+    std::io::stdout()
+        .write_all(reference.as_bytes())
+        .context("Failed to print reference")
+}
@@ -1,14 +1,23 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
 use chrono::NaiveDate;
 use clap::{Args, Subcommand};
+use clap_complete::engine::ArgValueCompleter;
+use pfo_client::{FundFilter, PfoClient};
 use pfo_core::sort::SortArguments;
 use serde::Serialize;
 
-use crate::fund::{FundInfo, FundInfoColumn, FundPriceStats, FundPriceStatsColumn};
+use crate::analytics::PriceSeries;
+use crate::cli::complete;
+use crate::fund::{
+    FundAnalysis, FundAnalysisColumn, FundInfo, FundInfoColumn, FundPriceStats,
+    FundPriceStatsColumn, FundWindowReturn,
+};
 use pfo_core::output::{Table, TableArgs};
 use pfo_core::parse_naive_date;
 
-use crate::client::PfoClient;
+use crate::store::Store;
 
 #[derive(Args, Serialize)]
 pub struct FundFilterArgs {
@@ -28,6 +37,15 @@ pub struct FundFilterArgs {
     pub codes: Vec<String>,
 }
 
+impl From<FundFilterArgs> for FundFilter {
+    fn from(args: FundFilterArgs) -> Self {
+        Self {
+            date: args.date,
+            codes: args.codes,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub enum FundCommand {
     #[command(name = "get", visible_alias = "g", about = "Get fund(s)")]
@@ -42,9 +60,18 @@ pub enum FundCommand {
             short,
             long,
             value_parser = SortArguments::<FundInfoColumn>::value_parser,
+            add = ArgValueCompleter::new(complete::sort::<FundInfoColumn>),
+            add = SortArguments::<FundInfoColumn>::doc(),
             help = SortArguments::<FundInfoColumn>::get_help()
         )]
         sort: Option<SortArguments<FundInfoColumn>>,
+
+        #[arg(
+            short,
+            long,
+            help = "Read fund informations archived by sync instead of the server"
+        )]
+        local: bool,
     },
 
     #[command(
@@ -67,31 +94,160 @@ pub enum FundCommand {
             short,
             long,
             value_parser = SortArguments::<FundPriceStatsColumn>::value_parser,
+            add = ArgValueCompleter::new(complete::sort::<FundPriceStatsColumn>),
+            add = SortArguments::<FundPriceStatsColumn>::doc(),
             help = SortArguments::<FundPriceStatsColumn>::get_help()
         )]
         sort: Option<SortArguments<FundPriceStatsColumn>>,
+
+        #[arg(
+            short,
+            long,
+            help = "Read fund price stats archived by sync instead of the server"
+        )]
+        local: bool,
+
+        #[arg(
+            short,
+            long,
+            requires = "local",
+            value_parser = parse_naive_date,
+            help = "Get archived fund price stats of given date. Latest archived date is used if omitted"
+        )]
+        date: Option<NaiveDate>,
+    },
+
+    #[command(
+        name = "analyze",
+        visible_alias = "an",
+        about = "Compute return statistics from fund prices archived by sync"
+    )]
+    Analyze {
+        #[arg(
+            value_name = "FUND_CODES",
+            value_delimiter = ',',
+            required = true,
+            help = "List of fund codes to analyze"
+        )]
+        codes: Vec<String>,
+
+        #[arg(
+            short,
+            long,
+            value_parser = parse_naive_date,
+            help = "Start of the price history. Earliest archived price is used if omitted"
+        )]
+        from: Option<NaiveDate>,
+
+        #[arg(
+            short,
+            long,
+            value_parser = parse_naive_date,
+            help = "End of the price history. Latest archived price is used if omitted"
+        )]
+        to: Option<NaiveDate>,
+
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.0,
+            help = "Annual risk-free rate used for Sharpe and Sortino ratios, e.g. 0.05 for 5%"
+        )]
+        risk_free_rate: f64,
+
+        #[arg(
+            long,
+            value_name = "DAYS",
+            value_delimiter = ',',
+            help = "Also print returns of the last DAYS days of the history for each window"
+        )]
+        windows: Vec<u32>,
+
+        #[command(flatten)]
+        output: TableArgs<FundAnalysisColumn>,
     },
 }
 
 impl FundCommand {
-    pub async fn handle(self, client: PfoClient) -> Result<()> {
+    pub async fn handle(self, client: PfoClient, database: Option<PathBuf>) -> Result<()> {
         match self {
             FundCommand::Get {
                 fund_filter,
                 output,
                 sort,
+                local,
             } => {
-                FundInfo::print_table(&client.get_funds(fund_filter, sort).await?, output);
+                let funds = if local {
+                    Store::open(database.as_deref())?.get_funds(
+                        &fund_filter.codes,
+                        fund_filter.date,
+                        sort,
+                    )?
+                } else {
+                    client
+                        .get_funds(fund_filter.into(), sort.map(Into::into))
+                        .await?
+                };
+
+                FundInfo::print_table(&funds, output);
             }
             FundCommand::PriceStats {
                 codes,
                 output,
                 sort,
+                local,
+                date,
             } => {
-                FundPriceStats::print_table(
-                    &client.get_fund_price_stats(codes, sort).await?,
-                    output,
-                );
+                let stats = if local {
+                    Store::open(database.as_deref())?.get_fund_price_stats(&codes, date, sort)?
+                } else {
+                    client
+                        .get_fund_price_stats(codes, sort.map(Into::into))
+                        .await?
+                };
+
+                FundPriceStats::print_table(&stats, output);
+            }
+            FundCommand::Analyze {
+                codes,
+                from,
+                to,
+                risk_free_rate,
+                windows,
+                output,
+            } => {
+                let store = Store::open(database.as_deref())?;
+
+                let mut analyses = Vec::with_capacity(codes.len());
+                let mut window_returns = Vec::with_capacity(codes.len() * windows.len());
+                for code in codes {
+                    let series = PriceSeries::new(store.get_fund_prices(&code, from, to)?);
+                    if series.len() < 2 {
+                        log::warn!("Not enough archived prices for {}, run sync first", code);
+                    }
+
+                    window_returns.extend(windows.iter().map(|&days| FundWindowReturn {
+                        code: code.clone(),
+                        days,
+                        window_return: series.window_return(days),
+                    }));
+                    analyses.push(FundAnalysis::new(code, &series, risk_free_rate));
+                }
+
+                let (no_headers, wide) = (output.no_headers, output.wide);
+                FundAnalysis::print_table(&analyses, output);
+
+                if !window_returns.is_empty() {
+                    println!();
+                    FundWindowReturn::print_table(
+                        &window_returns,
+                        TableArgs {
+                            columns: None,
+                            no_headers,
+                            wide,
+                        },
+                    );
+                }
             }
         }
 
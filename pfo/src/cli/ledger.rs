@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+use clap::Subcommand;
+use pfo_core::output::{Table, TableArgs};
+use pfo_core::parse_naive_date;
+use uuid::Uuid;
+
+use crate::error::PfoError;
+use crate::portfolio::{
+    PortfolioTransaction, PortfolioTransactionColumn, TransactionKind, TransactionRecord,
+};
+use crate::store::Store;
+
+#[derive(Subcommand)]
+pub enum LedgerCommand {
+    #[command(
+        name = "list",
+        visible_alias = "ls",
+        about = "List transactions recorded for a portfolio"
+    )]
+    List {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+        id: Uuid,
+
+        #[arg(
+            short,
+            long,
+            value_name = "FUND_CODE",
+            help = "Only list transactions of this fund"
+        )]
+        code: Option<String>,
+
+        #[command(flatten)]
+        output: TableArgs<PortfolioTransactionColumn>,
+    },
+
+    #[command(
+        name = "add",
+        visible_alias = "a",
+        about = "Record a past transaction without updating the portfolio"
+    )]
+    Add {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+        id: Uuid,
+
+        #[arg(
+            short,
+            long,
+            value_name = "FUND_CODE",
+            help = "Fund code of the transaction"
+        )]
+        code: String,
+
+        #[arg(short, long, value_parser = parse_naive_date, help = "Date of the transaction")]
+        date: NaiveDate,
+
+        #[arg(short, long, value_enum, default_value_t = TransactionKind::Buy, help = "Kind of the transaction")]
+        kind: TransactionKind,
+
+        #[arg(
+            short,
+            long,
+            value_parser = clap::value_parser!(u32).range(1..),
+            help = "Number of units bought or sold, at least 1"
+        )]
+        units: u32,
+
+        #[arg(short = 'P', long, help = "Price of a single unit")]
+        unit_price: f64,
+
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.0,
+            help = "Fees paid for the transaction"
+        )]
+        fees: f64,
+    },
+
+    #[command(
+        name = "import",
+        visible_alias = "i",
+        about = "Record transactions from a CSV file without updating the portfolio"
+    )]
+    Import {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+        id: Uuid,
+
+        #[arg(
+            value_name = "FILE",
+            help = "CSV file with code,date,kind,units,unit_price,fees header. Dates are in mm.dd.yyyy format, kind and fees are optional"
+        )]
+        file: PathBuf,
+    },
+
+    #[command(
+        name = "remove",
+        visible_alias = "rm",
+        about = "Remove a transaction from the ledger"
+    )]
+    Remove {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+        id: Uuid,
+
+        #[arg(
+            value_name = "TRANSACTION_ID",
+            help = "Transaction id as shown by list"
+        )]
+        transaction_id: u32,
+    },
+}
+
+impl LedgerCommand {
+    pub fn handle(self, database: Option<PathBuf>) -> Result<()> {
+        let mut store = Store::open(database.as_deref())?;
+
+        match self {
+            LedgerCommand::List { id, code, output } => {
+                PortfolioTransaction::print_table(
+                    &store.get_transactions(id, code.as_deref())?,
+                    output,
+                );
+            }
+            LedgerCommand::Add {
+                id,
+                code,
+                date,
+                kind,
+                units,
+                unit_price,
+                fees,
+            } => {
+                let record = TransactionRecord {
+                    code,
+                    date,
+                    kind,
+                    units,
+                    unit_price,
+                    fees,
+                };
+                let ids = store.add_transactions(id, &[record])?;
+
+                println!("Successfully recorded transaction {}", ids[0]);
+            }
+            LedgerCommand::Import { id, file } => {
+                let mut reader = csv::ReaderBuilder::new()
+                    .trim(csv::Trim::All)
+                    .from_path(&file)
+                    .context(format!("Failed to open {}", file.display()))?;
+                let records = reader
+                    .deserialize()
+                    .collect::<Result<Vec<TransactionRecord>, _>>()
+                    .context(format!(
+                        "Failed to parse transactions from {}",
+                        file.display()
+                    ))?;
+                if let Some(row) = records.iter().position(|r| r.units == 0) {
+                    // Rows are counted from 1 after the header
+                    bail!(PfoError::Validation(format!(
+                        "Transaction on row {} of {} has no units, at least 1 is required",
+                        row + 1,
+                        file.display()
+                    )));
+                }
+
+                let ids = store.add_transactions(id, &records)?;
+
+                println!("Successfully imported {} transactions", ids.len());
+            }
+            LedgerCommand::Remove { id, transaction_id } => {
+                store.remove_transaction(id, transaction_id)?;
+
+                println!("Successfully removed transaction {}", transaction_id);
+            }
+        }
+
+        Ok(())
+    }
+}
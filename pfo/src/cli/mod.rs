@@ -1,6 +1,19 @@
 mod args;
+mod complete;
+mod diff;
+mod docs;
 mod fund;
+mod ledger;
 mod portfolio;
+mod predictions;
+mod prompt;
+mod set;
+mod shell;
+mod sync;
+mod trade;
+mod update;
 
 pub use args::Args;
+pub use complete::completion_command;
 pub use fund::FundFilterArgs;
+pub use update::{UpdateOptions, UpdateReview, check_update, send_update};
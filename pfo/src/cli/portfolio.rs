@@ -1,19 +1,60 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use chrono::NaiveDate;
 use clap::Subcommand;
+use clap_complete::engine::ArgValueCompleter;
+use pfo_client::PfoClient;
 use pfo_core::output::{Table, TableArgs};
 use pfo_core::parse_naive_date;
 use pfo_core::sort::SortArguments;
 use uuid::Uuid;
 
-use crate::client::PfoClient;
+use crate::analytics::PriceSeries;
+use crate::cli::complete;
+use crate::cli::diff::DiffArgs;
+use crate::cli::ledger::LedgerCommand;
+use crate::cli::predictions::PredictionsArgs;
+use crate::cli::set::SetArgs;
+use crate::cli::trade::TradeArgs;
+use crate::cli::update::{UpdateOptions, review_update, send_update};
+use crate::error::PfoError;
 use crate::fund::{FundPriceStats, FundPriceStatsColumn};
 use crate::portfolio::{
-    Portfolio, PortfolioColumn, PortfolioFundPrediction, PortfolioFundPredictionColumn,
-    PortfolioFundPrice, PortfolioFundPriceColumn, PortfolioFundUpdate, PortfolioUpdate,
+    Portfolio, PortfolioColumn, PortfolioFundDrift, PortfolioFundDriftColumn,
+    PortfolioFundExposure, PortfolioFundExposureColumn, PortfolioFundPerformance,
+    PortfolioFundPerformanceColumn, PortfolioFundPrice, PortfolioFundPriceColumn,
+    PortfolioFundUpdate, PortfolioHolding, PortfolioHoldingColumn, PortfolioJournalEntry,
+    PortfolioJournalEntryColumn, PortfolioPerformance, PortfolioSnapshot, PortfolioSnapshotColumn,
+    PortfolioSummary, PortfolioUpdate, TransactionKind, Valuations,
 };
+use crate::store::Store;
+
+/// Fund prices of each of `portfolios`, fetched concurrently
+async fn fetch_holdings(
+    client: &PfoClient,
+    portfolios: &[Portfolio],
+    date: Option<NaiveDate>,
+    sort: Option<SortArguments<PortfolioFundPriceColumn>>,
+) -> Result<Vec<PortfolioHolding>> {
+    let prices =
+        client
+            .bulk(portfolios.iter().map(|p| {
+                client.get_portfolio_fund_prices(p.id, date, sort.clone().map(Into::into))
+            }))
+            .await?;
+
+    Ok(portfolios
+        .iter()
+        .zip(prices)
+        .flat_map(|(portfolio, prices)| {
+            prices
+                .into_iter()
+                .map(|price| PortfolioHolding::new(portfolio, price))
+        })
+        .collect())
+}
 
 #[derive(Subcommand)]
 pub enum PortfolioCommand {
@@ -26,6 +67,8 @@ pub enum PortfolioCommand {
             short,
             long,
             value_parser = SortArguments::<PortfolioColumn>::value_parser,
+            add = ArgValueCompleter::new(complete::sort::<PortfolioColumn>),
+            add = SortArguments::<PortfolioColumn>::doc(),
             help = SortArguments::<PortfolioColumn>::get_help()
         )]
         sort: Option<SortArguments<PortfolioColumn>>,
@@ -64,11 +107,60 @@ pub enum PortfolioCommand {
             short,
             long,
             value_parser = SortArguments::<PortfolioFundPriceColumn>::value_parser,
+            add = ArgValueCompleter::new(complete::sort::<PortfolioFundPriceColumn>),
+            add = SortArguments::<PortfolioFundPriceColumn>::doc(),
             help = SortArguments::<PortfolioFundPriceColumn>::get_help()
         )]
         sort: Option<SortArguments<PortfolioFundPriceColumn>>,
     },
 
+    #[command(
+        name = "holdings",
+        about = "Get fund prices of several portfolios at once, all portfolios by default"
+    )]
+    Holdings {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUIDs")]
+        ids: Vec<Uuid>,
+
+        #[command(flatten)]
+        output: TableArgs<PortfolioHoldingColumn>,
+
+        #[arg(
+            short,
+            long,
+            value_parser = parse_naive_date,
+            help = "Get fund prices only for given date. Latest date is used by server if omitted"
+        )]
+        date: Option<NaiveDate>,
+
+        #[arg(
+            short,
+            long,
+            value_parser = SortArguments::<PortfolioFundPriceColumn>::value_parser,
+            add = ArgValueCompleter::new(complete::sort::<PortfolioFundPriceColumn>),
+            add = SortArguments::<PortfolioFundPriceColumn>::doc(),
+            help = SortArguments::<PortfolioFundPriceColumn>::get_help()
+        )]
+        sort: Option<SortArguments<PortfolioFundPriceColumn>>,
+    },
+
+    #[command(
+        name = "overview",
+        about = "Get total units, money spent and value of each fund over all portfolios, followed by totals of each portfolio"
+    )]
+    Overview {
+        #[command(flatten)]
+        output: TableArgs<PortfolioFundExposureColumn>,
+
+        #[arg(
+            short,
+            long,
+            value_parser = parse_naive_date,
+            help = "Use fund prices of given date. Latest date is used by server if omitted"
+        )]
+        date: Option<NaiveDate>,
+    },
+
     #[command(
         name = "predictions",
         visible_alias = "P",
@@ -76,14 +168,8 @@ pub enum PortfolioCommand {
         about = "Get how much to spend for each fund in a portfolio"
     )]
     Predictions {
-        #[arg(value_name = "PORTFOLIO_ID", help = "Portoflio UUID")]
-        id: Uuid,
-
-        #[arg(short, long, help = "Budget to spend on funds")]
-        budget: f32,
-
         #[command(flatten)]
-        output: TableArgs<PortfolioFundPredictionColumn>,
+        args: PredictionsArgs,
     },
 
     #[command(
@@ -102,6 +188,8 @@ pub enum PortfolioCommand {
             short,
             long,
             value_parser = SortArguments::<FundPriceStatsColumn>::value_parser,
+            add = ArgValueCompleter::new(complete::sort::<FundPriceStatsColumn>),
+            add = SortArguments::<FundPriceStatsColumn>::doc(),
             help = SortArguments::<FundPriceStatsColumn>::get_help()
         )]
         sort: Option<SortArguments<FundPriceStatsColumn>>,
@@ -136,6 +224,15 @@ pub enum PortfolioCommand {
         total_money_spent: Option<f64>,
     },
 
+    #[command(
+        name = "set",
+        about = "Change settings of a fund already in a portfolio"
+    )]
+    Set {
+        #[command(flatten)]
+        args: SetArgs,
+    },
+
     #[command(
         name = "remove",
         visible_alias = "rm",
@@ -153,10 +250,159 @@ pub enum PortfolioCommand {
         )]
         codes: Vec<String>,
     },
+
+    #[command(
+        name = "buy",
+        about = "Record a purchase in the ledger and add bought units to the portfolio"
+    )]
+    Buy {
+        #[command(flatten)]
+        trade: TradeArgs,
+    },
+
+    #[command(
+        name = "sell",
+        about = "Record a sale in the ledger and remove sold units from the portfolio"
+    )]
+    Sell {
+        #[command(flatten)]
+        trade: TradeArgs,
+    },
+
+    #[command(
+        name = "drift",
+        about = "Compare market value share of each fund with its weight, failing when any is out of tolerance",
+        long_about = "Compare market value share of each fund with its weight. Exits with code 9 when any \
+            fund is out of tolerance band, after printing the report"
+    )]
+    Drift {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+        id: Uuid,
+
+        #[arg(
+            short,
+            long,
+            value_parser = parse_naive_date,
+            help = "Use fund prices of given date. Latest date is used by server if omitted"
+        )]
+        date: Option<NaiveDate>,
+
+        #[arg(
+            short,
+            long,
+            default_value_t = 0.05,
+            help = "Largest allowed difference between value share and weight, e.g. 0.05 for 5 percentage points"
+        )]
+        tolerance: f64,
+
+        #[arg(
+            short,
+            long,
+            help = "Largest allowed difference relative to weight, e.g. 0.25 for 25% of the weight"
+        )]
+        relative_tolerance: Option<f64>,
+
+        #[command(flatten)]
+        output: TableArgs<PortfolioFundDriftColumn>,
+    },
+
+    #[command(
+        name = "ledger",
+        visible_alias = "l",
+        about = "Manage locally recorded transactions of a portfolio"
+    )]
+    Ledger {
+        #[command(subcommand)]
+        command: LedgerCommand,
+    },
+
+    #[command(
+        name = "history",
+        about = "List updates sent to a portfolio, as recorded in the local journal"
+    )]
+    History {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+        id: Uuid,
+
+        #[command(flatten)]
+        output: TableArgs<PortfolioJournalEntryColumn>,
+    },
+
+    #[command(
+        name = "undo",
+        about = "Revert latest updates of a portfolio that are recorded in the local journal",
+        long_about = "Revert latest updates of a portfolio that are recorded in the local journal. \
+            Ledger transactions recorded by buy, sell and predictions --apply for an update are removed \
+            together with it"
+    )]
+    Undo {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+        id: Uuid,
+
+        #[arg(
+            value_name = "N",
+            default_value_t = 1,
+            help = "Number of updates to revert, newest first"
+        )]
+        count: usize,
+    },
+
+    #[command(
+        name = "snapshot",
+        about = "Save current fund prices of a portfolio locally, to compare them later with diff"
+    )]
+    Snapshot {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+        id: Uuid,
+    },
+
+    #[command(name = "snapshots", about = "List saved snapshots of a portfolio")]
+    Snapshots {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+        id: Uuid,
+
+        #[command(flatten)]
+        output: TableArgs<PortfolioSnapshotColumn>,
+    },
+
+    #[command(
+        name = "diff",
+        about = "Show how funds of a portfolio changed between two snapshots or dates"
+    )]
+    Diff {
+        #[command(flatten)]
+        args: DiffArgs,
+    },
+
+    #[command(
+        name = "performance",
+        visible_alias = "perf",
+        about = "Get money-weighted (XIRR) and time-weighted returns from recorded transactions"
+    )]
+    Performance {
+        #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+        id: Uuid,
+
+        #[arg(
+            short,
+            long,
+            value_parser = parse_naive_date,
+            help = "Value holdings at given date, ignoring later transactions. Latest date is used by server if omitted"
+        )]
+        date: Option<NaiveDate>,
+
+        #[command(flatten)]
+        output: TableArgs<PortfolioFundPerformanceColumn>,
+    },
 }
 
 impl PortfolioCommand {
-    pub async fn handle(self, client: PfoClient) -> Result<()> {
+    pub async fn handle(
+        self,
+        client: PfoClient,
+        database: Option<PathBuf>,
+        options: UpdateOptions,
+    ) -> Result<()> {
         match self {
             PortfolioCommand::List { output, .. } => {
                 Portfolio::print_table(&client.list_portfolios().await?, output);
@@ -171,15 +417,54 @@ impl PortfolioCommand {
                 sort,
             } => {
                 PortfolioFundPrice::print_table(
-                    &client.get_portfolio_fund_prices(id, date, sort).await?,
+                    &client
+                        .get_portfolio_fund_prices(id, date, sort.map(Into::into))
+                        .await?,
                     output,
                 );
             }
-            PortfolioCommand::Predictions { id, budget, output } => {
-                PortfolioFundPrediction::print_table(
-                    &client.get_portfolio_fund_predictions(id, budget).await?,
+            PortfolioCommand::Holdings {
+                ids,
+                output,
+                date,
+                sort,
+            } => {
+                let mut portfolios = client.list_portfolios().await?;
+                if !ids.is_empty() {
+                    if let Some(missing) = ids
+                        .iter()
+                        .find(|id| !portfolios.iter().any(|p| p.id == **id))
+                    {
+                        bail!(PfoError::NotFound(format!("No portfolio {}", missing)));
+                    }
+                    portfolios.retain(|p| ids.contains(&p.id));
+                }
+
+                let holdings = fetch_holdings(&client, &portfolios, date, sort).await?;
+                PortfolioHolding::print_table(&holdings, output);
+            }
+            PortfolioCommand::Overview { date, output } => {
+                let portfolios = client.list_portfolios().await?;
+                let holdings = fetch_holdings(&client, &portfolios, date, None).await?;
+
+                let (no_headers, wide) = (output.no_headers, output.wide);
+                PortfolioFundExposure::print_table(
+                    &PortfolioFundExposure::merge(&holdings),
                     output,
                 );
+
+                println!();
+                PortfolioSummary::print_table(
+                    &PortfolioSummary::summarize(&portfolios, &holdings),
+                    TableArgs {
+                        columns: None,
+                        no_headers,
+                        wide,
+                    },
+                );
+            }
+            PortfolioCommand::Predictions { args } => {
+                args.handle(client, database, options).await?
             }
             PortfolioCommand::Add {
                 id,
@@ -204,8 +489,12 @@ impl PortfolioCommand {
                     remove_codes: HashSet::new(),
                 };
 
-                client
-                    .update_portfolio(id, update)
+                if !review_update(&client, id, &update, options).await? {
+                    return Ok(());
+                }
+
+                let mut store = Store::open(database.as_deref())?;
+                send_update(&client, &mut store, id, update, &[])
                     .await
                     .context("Failed to add fund to portfolio")?;
 
@@ -220,19 +509,199 @@ impl PortfolioCommand {
                     remove_codes: fund_codes.into_iter().collect(),
                 };
 
-                client
-                    .update_portfolio(id, update)
+                if !review_update(&client, id, &update, options).await? {
+                    return Ok(());
+                }
+
+                let mut store = Store::open(database.as_deref())?;
+                send_update(&client, &mut store, id, update, &[])
                     .await
                     .context("Failed to remove funds from portfolio")?;
 
                 println!("Successfully removed funds");
             }
+            PortfolioCommand::Set { args } => args.handle(client, database, options).await?,
             PortfolioCommand::PriceStats { id, output, sort } => {
                 FundPriceStats::print_table(
-                    &client.get_portfolio_fund_price_stats(id, sort).await?,
+                    &client
+                        .get_portfolio_fund_price_stats(id, sort.map(Into::into))
+                        .await?,
                     output,
                 );
             }
+            PortfolioCommand::Buy { trade } => {
+                trade
+                    .handle(TransactionKind::Buy, client, database, options)
+                    .await?
+            }
+            PortfolioCommand::Sell { trade } => {
+                trade
+                    .handle(TransactionKind::Sell, client, database, options)
+                    .await?
+            }
+            PortfolioCommand::Drift {
+                id,
+                date,
+                tolerance,
+                relative_tolerance,
+                output,
+            } => {
+                let drifts = PortfolioFundDrift::from_prices(
+                    &client.get_portfolio_fund_prices(id, date, None).await?,
+                    tolerance,
+                    relative_tolerance,
+                );
+                PortfolioFundDrift::print_table(&drifts, output);
+
+                let out_of_band = drifts.iter().filter(|d| d.out_of_band).count();
+                if out_of_band > 0 {
+                    bail!(PfoError::OutOfBand(format!(
+                        "{} funds are out of tolerance band",
+                        out_of_band
+                    )));
+                }
+            }
+            PortfolioCommand::Ledger { command } => command.handle(database)?,
+            PortfolioCommand::Snapshot { id } => {
+                let mut store = Store::open(database.as_deref())?;
+                let prices = client.get_portfolio_fund_prices(id, None, None).await?;
+                let snapshot_id = store.add_snapshot(id, &prices)?;
+
+                println!("Saved snapshot {} with {} funds", snapshot_id, prices.len());
+            }
+            PortfolioCommand::Snapshots { id, output } => {
+                let store = Store::open(database.as_deref())?;
+                PortfolioSnapshot::print_table(&store.get_snapshots(id)?, output);
+            }
+            PortfolioCommand::Diff { args } => args.handle(client, database).await?,
+            PortfolioCommand::History { id, output } => {
+                let store = Store::open(database.as_deref())?;
+                PortfolioJournalEntry::print_table(&store.get_journal(id)?, output);
+            }
+            PortfolioCommand::Undo { id, count } => {
+                let mut store = Store::open(database.as_deref())?;
+
+                let entries: Vec<_> = store
+                    .get_journal(id)?
+                    .into_iter()
+                    .filter(|e| !e.undone)
+                    .take(count)
+                    .collect();
+                if entries.len() < count {
+                    bail!(PfoError::Validation(format!(
+                        "Only {} updates of portfolio {} can be undone",
+                        entries.len(),
+                        id
+                    )));
+                }
+
+                for entry in entries {
+                    let record = store.get_journal_record(id, entry.id)?;
+                    let current = client.get_portfolio_fund_prices(id, None, None).await?;
+                    let inverse = record.inverse(&current);
+
+                    if !review_update(&client, id, &inverse, options).await? {
+                        if options.dry_run {
+                            continue;
+                        }
+                        return Ok(());
+                    }
+
+                    client
+                        .update_portfolio(id, inverse)
+                        .await
+                        .context(format!("Failed to undo update {}", entry.id))?;
+                    let removed = store.undo_journal_entry(id, entry.id)?;
+
+                    println!("Successfully undid update {}", entry.id);
+                    if removed > 0 {
+                        println!("Removed {} ledger transactions recorded for it", removed);
+                    }
+                }
+            }
+            PortfolioCommand::Performance { id, date, output } => {
+                let store = Store::open(database.as_deref())?;
+
+                let mut transactions = store.get_transactions(id, None)?;
+                if let Some(date) = date {
+                    transactions.retain(|t| t.date <= date);
+                }
+                if transactions.is_empty() {
+                    bail!(PfoError::NotFound(format!(
+                        "No transactions recorded for portfolio {}, record them with ledger add or ledger import",
+                        id
+                    )));
+                }
+
+                let portfolio = client.get_portfolio(id).await?;
+                let prices = client.get_portfolio_fund_prices(id, date, None).await?;
+
+                let mut codes: Vec<&str> = transactions.iter().map(|t| t.code.as_str()).collect();
+                codes.sort();
+                codes.dedup();
+
+                let mut series: HashMap<&str, PriceSeries> = HashMap::with_capacity(codes.len());
+                for code in &codes {
+                    let mut points: Vec<_> = prices
+                        .iter()
+                        .filter(|p| p.code == *code)
+                        .map(|p| (p.date, p.price))
+                        .collect();
+                    points.extend(store.get_fund_prices(code, None, date)?);
+                    points.extend(
+                        transactions
+                            .iter()
+                            .filter(|t| t.code == *code)
+                            .map(|t| (t.date, t.unit_price)),
+                    );
+
+                    series.insert(code, PriceSeries::new(points));
+                }
+
+                let valuations: Valuations = codes
+                    .iter()
+                    .filter_map(|code| {
+                        let valuation = match prices.iter().find(|p| p.code == *code) {
+                            Some(price) => Some((price.date, price.price)),
+                            None => series.get(code)?.last(),
+                        };
+                        valuation.map(|v| (*code, v))
+                    })
+                    .collect();
+                let price_at = |code: &str, date: NaiveDate| series.get(code)?.price_at(date);
+
+                let funds: Vec<PortfolioFundPerformance> = codes
+                    .iter()
+                    .map(|code| {
+                        let fund_transactions: Vec<_> =
+                            transactions.iter().filter(|t| t.code == *code).collect();
+                        PortfolioFundPerformance::new(
+                            code.to_string(),
+                            &fund_transactions,
+                            &valuations,
+                            price_at,
+                        )
+                    })
+                    .collect();
+                let total = PortfolioPerformance::new(
+                    portfolio.name,
+                    &transactions.iter().collect::<Vec<_>>(),
+                    &valuations,
+                    price_at,
+                );
+
+                let (no_headers, wide) = (output.no_headers, output.wide);
+                PortfolioFundPerformance::print_table(&funds, output);
+                println!();
+                PortfolioPerformance::print_table(
+                    &[total],
+                    TableArgs {
+                        columns: None,
+                        no_headers,
+                        wide,
+                    },
+                );
+            }
         }
 
         Ok(())
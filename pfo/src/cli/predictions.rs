@@ -0,0 +1,455 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory};
+use pfo_client::PfoClient;
+use pfo_core::output::{Table, TableArgs, print_dynamic_table};
+use uuid::Uuid;
+
+use crate::analytics::{AllocationFund, allocate};
+use crate::cli::args;
+use crate::cli::prompt::confirm;
+use crate::cli::update::{UpdateOptions, review_update, send_update};
+use crate::portfolio::{
+    PortfolioFundPrediction, PortfolioFundPredictionAllocation,
+    PortfolioFundPredictionAllocationColumn, PortfolioFundPredictionComparison, PortfolioFundPrice,
+    PortfolioFundPurchase, PortfolioFundUpdate, PortfolioUpdate, TransactionKind,
+    TransactionRecord, predictions_cost, unit_price,
+};
+use crate::store::Store;
+
+/// Most budgets of a `--budget-range`, each one is a request to the server
+const MAX_BUDGETS: usize = 10_000;
+
+/// Inclusive range of budgets, given as `<start>..<end>`
+#[derive(Clone, Debug)]
+pub struct BudgetRange {
+    start: f64,
+    end: f64,
+}
+
+impl FromStr for BudgetRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once("..")
+            .ok_or_else(|| format!("Budget range must be in <start>..<end> format: {}", s))?;
+        let parse = |v: &str| {
+            v.trim()
+                .parse::<f64>()
+                .map_err(|err| format!("Invalid budget {}: {}", v, err))
+        };
+
+        let range = Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        };
+        if range.start > range.end {
+            return Err(format!("Budget range start is larger than its end: {}", s));
+        }
+
+        Ok(range)
+    }
+}
+
+impl BudgetRange {
+    /// Budgets from start by `step`, including end when it is a whole number of steps away despite
+    /// rounding
+    fn budgets(&self, step: f64) -> Result<Vec<f64>, String> {
+        let count = ((self.end - self.start) / step + 1e-9).floor();
+        if count >= MAX_BUDGETS as f64 {
+            return Err(format!(
+                "Budget range {}..{} with step {} has more than {} budgets",
+                self.start, self.end, step, MAX_BUDGETS
+            ));
+        }
+
+        Ok((0..=count as usize)
+            .map(|i| (self.start + i as f64 * step).min(self.end))
+            .collect())
+    }
+}
+
+fn parse_step(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(step) if step > 0.0 => Ok(step),
+        Ok(_) => Err("Step must be positive".into()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[derive(Args)]
+pub struct PredictionsArgs {
+    #[arg(value_name = "PORTFOLIO_ID", help = "Portoflio UUID")]
+    id: Uuid,
+
+    #[arg(
+        short,
+        long,
+        required_unless_present = "budget_range",
+        help = "Budget to spend on funds"
+    )]
+    budget: Option<f32>,
+
+    #[arg(
+        long,
+        value_name = "START..END",
+        requires = "step",
+        conflicts_with_all = ["budget", "apply", "compare"],
+        help = "Get predictions for each budget from START to END, both inclusive, and print amounts of each fund per budget"
+    )]
+    budget_range: Option<BudgetRange>,
+
+    #[arg(
+        long,
+        requires = "budget_range",
+        conflicts_with = "budget",
+        value_parser = parse_step,
+        help = "Increment between budgets of --budget-range"
+    )]
+    step: Option<f64>,
+
+    #[arg(
+        long,
+        requires = "budget_range",
+        conflicts_with = "budget",
+        help = "Print budget sweep as CSV instead of a table"
+    )]
+    csv: bool,
+
+    #[arg(
+        short,
+        long,
+        help = "Allocate the budget locally from fund prices, weights and owned amounts instead of asking the server"
+    )]
+    local: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["local", "apply"],
+        help = "Print amounts predicted by the server next to amounts allocated locally"
+    )]
+    compare: bool,
+
+    #[arg(
+        long,
+        help = "Buy predicted amounts: record them in the ledger and add them to owned amounts and money spent"
+    )]
+    apply: bool,
+
+    #[command(flatten)]
+    output: TableArgs<PortfolioFundPredictionAllocationColumn>,
+}
+
+impl PredictionsArgs {
+    pub async fn handle(
+        self,
+        client: PfoClient,
+        database: Option<PathBuf>,
+        options: UpdateOptions,
+    ) -> Result<()> {
+        if let (Some(range), Some(step)) = (&self.budget_range, self.step) {
+            let budgets = range
+                .budgets(step)
+                .map_err(|msg| args::Args::command().error(ErrorKind::ValueValidation, msg))?;
+            return self.sweep(&client, budgets).await;
+        }
+
+        let budget = self
+            .budget
+            .context("Either --budget or --budget-range is required")?;
+
+        if self.compare {
+            return self.compare(client, budget).await;
+        }
+
+        let prices = client
+            .get_portfolio_fund_prices(self.id, None, None)
+            .await?;
+        let predictions = if self.local {
+            Self::allocate(&prices, budget as f64)
+        } else {
+            client
+                .get_portfolio_fund_predictions(self.id, budget)
+                .await?
+        };
+
+        if self.apply {
+            return self
+                .apply(client, database, options, budget, &predictions, &prices)
+                .await;
+        }
+
+        let allocations =
+            PortfolioFundPredictionAllocation::from_predictions(&predictions, &prices);
+        let total_cost: f64 = allocations.iter().map(|a| a.cost).sum();
+
+        PortfolioFundPredictionAllocation::print_table(&allocations, self.output);
+        println!("Total cost: {:.6}", total_cost);
+        println!("Leftover: {:.6}", budget as f64 - total_cost);
+
+        Ok(())
+    }
+
+    fn allocate(prices: &[PortfolioFundPrice], budget: f64) -> Vec<PortfolioFundPrediction> {
+        let funds: Vec<AllocationFund> = prices
+            .iter()
+            .map(|p| AllocationFund {
+                price: p.price,
+                weight: p.normalized_weight as f64,
+                owned: p.owned_amount,
+                min_amount: p.min_amount,
+            })
+            .collect();
+        let amounts = allocate(&funds, budget);
+
+        prices
+            .iter()
+            .zip(amounts)
+            .map(|(price, amount)| PortfolioFundPrediction {
+                code: price.code.clone(),
+                title: price.title.clone(),
+                price: price.price as f32,
+                amount,
+                weight: price.normalized_weight,
+            })
+            .collect()
+    }
+
+    async fn sweep(&self, client: &PfoClient, budgets: Vec<f64>) -> Result<()> {
+        let prices = client
+            .get_portfolio_fund_prices(self.id, None, None)
+            .await?;
+        let results: Vec<Vec<PortfolioFundPrediction>> = if self.local {
+            budgets
+                .iter()
+                .map(|b| Self::allocate(&prices, *b))
+                .collect()
+        } else {
+            client
+                .bulk(
+                    budgets
+                        .iter()
+                        .map(|b| client.get_portfolio_fund_predictions(self.id, *b as f32)),
+                )
+                .await?
+        };
+
+        let mut codes: Vec<&str> = Vec::new();
+        for prediction in results.iter().flatten() {
+            if !codes.contains(&prediction.code.as_str()) {
+                codes.push(&prediction.code);
+            }
+        }
+
+        let mut headers = vec!["Budget".to_string()];
+        headers.extend(codes.iter().map(|c| c.to_string()));
+        headers.extend(["Cost".to_string(), "Leftover".to_string()]);
+
+        let rows: Vec<Vec<String>> = budgets
+            .iter()
+            .zip(&results)
+            .map(|(budget, predictions)| {
+                let cost = predictions_cost(predictions, &prices);
+
+                let mut row = vec![format!("{:.2}", budget)];
+                row.extend(codes.iter().map(|code| {
+                    predictions
+                        .iter()
+                        .find(|p| p.code == *code)
+                        .map_or(0, |p| p.amount)
+                        .to_string()
+                }));
+                row.push(format!("{:.6}", cost));
+                row.push(format!("{:.6}", budget - cost));
+                row
+            })
+            .collect();
+
+        if self.csv {
+            let mut writer = csv::Writer::from_writer(io::stdout());
+            if !self.output.no_headers {
+                writer.write_record(&headers)?;
+            }
+            for row in rows {
+                writer.write_record(&row)?;
+            }
+            writer.flush()?;
+        } else {
+            print_dynamic_table(
+                &headers,
+                &vec![false; headers.len()],
+                &rows,
+                self.output.no_headers,
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn compare(&self, client: PfoClient, budget: f32) -> Result<()> {
+        let server = client
+            .get_portfolio_fund_predictions(self.id, budget)
+            .await?;
+        let prices = client
+            .get_portfolio_fund_prices(self.id, None, None)
+            .await?;
+        let local = Self::allocate(&prices, budget as f64);
+
+        let mut comparisons: Vec<PortfolioFundPredictionComparison> = local
+            .iter()
+            .map(|l| {
+                let server_amount = server
+                    .iter()
+                    .find(|s| s.code == l.code)
+                    .map_or(0, |s| s.amount);
+
+                PortfolioFundPredictionComparison {
+                    code: l.code.clone(),
+                    title: l.title.clone(),
+                    price: l.price,
+                    server_amount,
+                    local_amount: l.amount,
+                    difference: i64::from(l.amount) - i64::from(server_amount),
+                }
+            })
+            .collect();
+        comparisons.extend(
+            server
+                .iter()
+                .filter(|s| !local.iter().any(|l| l.code == s.code))
+                .map(|s| PortfolioFundPredictionComparison {
+                    code: s.code.clone(),
+                    title: s.title.clone(),
+                    price: s.price,
+                    server_amount: s.amount,
+                    local_amount: 0,
+                    difference: -i64::from(s.amount),
+                }),
+        );
+
+        PortfolioFundPredictionComparison::print_table(
+            &comparisons,
+            TableArgs {
+                columns: None,
+                no_headers: self.output.no_headers,
+                wide: self.output.wide,
+            },
+        );
+        println!("Server cost: {:.6}", predictions_cost(&server, &prices));
+        println!("Local cost: {:.6}", predictions_cost(&local, &prices));
+
+        Ok(())
+    }
+
+    async fn apply(
+        &self,
+        client: PfoClient,
+        database: Option<PathBuf>,
+        options: UpdateOptions,
+        budget: f32,
+        predictions: &[PortfolioFundPrediction],
+        prices: &[PortfolioFundPrice],
+    ) -> Result<()> {
+        let purchases: Vec<PortfolioFundPurchase> = predictions
+            .iter()
+            .filter(|p| p.amount > 0)
+            .map(|prediction| {
+                let price = unit_price(prediction, prices);
+                let cost = prediction.amount as f64 * price;
+                let (owned, spent) = prices
+                    .iter()
+                    .find(|p| p.code == prediction.code)
+                    .map_or((0, 0.0), |p| (p.owned_amount, p.money_spent));
+
+                PortfolioFundPurchase {
+                    code: prediction.code.clone(),
+                    amount: prediction.amount,
+                    price,
+                    cost,
+                    owned_amount: owned + prediction.amount,
+                    money_spent: spent + cost,
+                }
+            })
+            .collect();
+
+        if purchases.is_empty() {
+            println!("Nothing to buy with budget {}", budget);
+            return Ok(());
+        }
+
+        let total_cost: f64 = purchases.iter().map(|p| p.cost).sum();
+        PortfolioFundPurchase::print_table(
+            &purchases,
+            TableArgs {
+                columns: None,
+                no_headers: self.output.no_headers,
+                wide: self.output.wide,
+            },
+        );
+        println!("Total cost: {:.6}", total_cost);
+        println!("Leftover: {:.6}", budget as f64 - total_cost);
+
+        let update = PortfolioUpdate {
+            add_codes: purchases
+                .iter()
+                .map(|p| PortfolioFundUpdate {
+                    fund_code: p.code.clone(),
+                    weight: None,
+                    min_amount: None,
+                    owned_amount: Some(p.owned_amount),
+                    total_money_spent: Some(p.money_spent),
+                })
+                .collect::<HashSet<_>>(),
+            remove_codes: HashSet::new(),
+        };
+
+        if !review_update(&client, self.id, &update, options).await? {
+            return Ok(());
+        }
+
+        if !options.yes && !confirm("Apply these purchases?")? {
+            println!("Aborted");
+            return Ok(());
+        }
+
+        let mut store = Store::open(database.as_deref())?;
+        let date = Local::now().date_naive();
+        let records: Vec<TransactionRecord> = purchases
+            .iter()
+            .map(|p| TransactionRecord {
+                code: p.code.clone(),
+                date,
+                kind: TransactionKind::Buy,
+                units: p.amount,
+                unit_price: p.price,
+                fees: 0.0,
+            })
+            .collect();
+        let transaction_ids = store.add_transactions(self.id, &records)?;
+
+        if let Err(err) = send_update(&client, &mut store, self.id, update, &transaction_ids).await
+        {
+            for transaction_id in transaction_ids {
+                if let Err(rollback) = store.remove_transaction(self.id, transaction_id) {
+                    log::error!(
+                        "Failed to remove transaction {} of the failed update: {:#}",
+                        transaction_id,
+                        rollback
+                    );
+                }
+            }
+            return Err(err).context("Failed to apply predictions");
+        }
+
+        println!("Successfully applied {} purchases", purchases.len());
+
+        Ok(())
+    }
+}
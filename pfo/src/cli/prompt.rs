@@ -0,0 +1,17 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::{Context, Result};
+
+/// Ask a yes/no question on stderr, anything other than `y` or `yes` is a no
+pub fn confirm(question: &str) -> Result<bool> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin()
+        .lock()
+        .read_line(&mut answer)
+        .context("Failed to read answer")?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::ops::{Add, Sub};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail};
+use clap::{ArgGroup, Args};
+use pfo_client::PfoClient;
+use uuid::Uuid;
+
+use crate::cli::update::{UpdateOptions, review_update, send_update};
+use crate::error::PfoError;
+use crate::portfolio::{PortfolioFundUpdate, PortfolioUpdate};
+use crate::store::Store;
+
+/// New value of a setting, either absolute or relative to the current value with a `+`/`-` prefix
+#[derive(Clone, Debug)]
+pub enum Change<T> {
+    Set(T),
+    Increase(T),
+    Decrease(T),
+}
+
+impl<T: FromStr> FromStr for Change<T>
+where
+    T::Err: Display,
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |v: &str| v.parse::<T>().map_err(|err| format!("{}: {}", err, s));
+
+        if let Some(v) = s.strip_prefix('+') {
+            Ok(Change::Increase(parse(v)?))
+        } else if let Some(v) = s.strip_prefix('-') {
+            Ok(Change::Decrease(parse(v)?))
+        } else {
+            Ok(Change::Set(parse(s)?))
+        }
+    }
+}
+
+impl<T> Change<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Display,
+{
+    fn apply(&self, current: T, name: &str) -> Result<T> {
+        match *self {
+            Change::Set(v) => Ok(v),
+            Change::Increase(v) => Ok(current + v),
+            Change::Decrease(v) if v > current => {
+                bail!(PfoError::Validation(format!(
+                    "Cannot decrease {} {} by {}",
+                    name, current, v
+                )))
+            }
+            Change::Decrease(v) => Ok(current - v),
+        }
+    }
+}
+
+#[derive(Args)]
+#[command(group(
+    ArgGroup::new("settings")
+        .required(true)
+        .multiple(true)
+        .args(["weight", "min_amount", "owned", "spent"])
+))]
+pub struct SetArgs {
+    #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+    id: Uuid,
+
+    #[arg(
+        value_name = "FUND_CODE",
+        help = "Code of a fund already in the portfolio"
+    )]
+    code: String,
+
+    #[arg(
+        short,
+        long,
+        help = "New weight of the fund, higher means more preferred. Relative changes are not supported since server only reports normalized weights"
+    )]
+    weight: Option<u32>,
+
+    #[arg(
+        short,
+        long,
+        allow_hyphen_values = true,
+        help = "Minimum number of amounts to buy, or +N/-N to change it by N"
+    )]
+    min_amount: Option<Change<u32>>,
+
+    #[arg(
+        short,
+        long,
+        allow_hyphen_values = true,
+        help = "Owned amount, or +N/-N to change it by N"
+    )]
+    owned: Option<Change<u32>>,
+
+    #[arg(
+        short,
+        long,
+        allow_hyphen_values = true,
+        help = "Total money spent for owned amount, or +N/-N to change it by N"
+    )]
+    spent: Option<Change<f64>>,
+}
+
+impl SetArgs {
+    pub async fn handle(
+        self,
+        client: PfoClient,
+        database: Option<PathBuf>,
+        options: UpdateOptions,
+    ) -> Result<()> {
+        let Some(current) = client
+            .get_portfolio_fund_prices(self.id, None, None)
+            .await?
+            .into_iter()
+            .find(|p| p.code == self.code)
+        else {
+            bail!(PfoError::NotFound(format!(
+                "Fund {} is not in portfolio {}, use add to add it",
+                self.code, self.id
+            )));
+        };
+
+        let min_amount = self
+            .min_amount
+            .map(|c| c.apply(current.min_amount, "min amount"))
+            .transpose()?;
+        let owned = self
+            .owned
+            .map(|c| c.apply(current.owned_amount, "owned amount"))
+            .transpose()?;
+        let spent = self
+            .spent
+            .map(|c| c.apply(current.money_spent, "money spent"))
+            .transpose()?;
+
+        let update = PortfolioUpdate {
+            add_codes: HashSet::from([PortfolioFundUpdate {
+                fund_code: self.code.clone(),
+                weight: self.weight,
+                min_amount,
+                owned_amount: owned,
+                total_money_spent: spent,
+            }]),
+            remove_codes: HashSet::new(),
+        };
+
+        if !review_update(&client, self.id, &update, options).await? {
+            return Ok(());
+        }
+
+        let mut store = Store::open(database.as_deref())?;
+        send_update(&client, &mut store, self.id, update, &[])
+            .await
+            .context(format!("Failed to update fund {}", self.code))?;
+
+        println!("Successfully updated fund {}", self.code);
+        if let Some(weight) = self.weight {
+            println!(
+                "Weight: {:.2} (normalized) -> {}",
+                current.normalized_weight, weight
+            );
+        }
+        if let Some(min_amount) = min_amount {
+            println!("Min amount: {} -> {}", current.min_amount, min_amount);
+        }
+        if let Some(owned) = owned {
+            println!("Owned: {} -> {}", current.owned_amount, owned);
+        }
+        if let Some(spent) = spent {
+            println!("Money spent: {:.6} -> {:.6}", current.money_spent, spent);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,457 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use clap::parser::ValueSource;
+use clap::{Arg, Command, CommandFactory, FromArgMatches};
+use pfo_client::PfoClient;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::{CompletionType, Config, Editor, Helper, Highlighter, Hinter, Validator};
+use uuid::Uuid;
+
+use crate::cli::args::{Args, Commands};
+use crate::cli::update::UpdateOptions;
+use crate::error::{self, ErrorFormat, PfoError};
+use crate::portfolio::Portfolio;
+use crate::store::Store;
+
+/// Commands of the shell itself, completed along with subcommands of [`Args`]
+const BUILTINS: [&str; 3] = ["use", "exit", "quit"];
+
+/// Global options of [`Args`] applied to a single command of the shell, others are fixed when the
+/// shell starts
+const SHELL_GLOBALS: [&str; 3] = ["database", "dry_run", "yes"];
+
+/// Subcommand of `command` reached by `words` and the index of the first word after it. Options
+/// are skipped, together with their values.
+fn find_leaf<'a, S: AsRef<str>>(command: &'a Command, words: &[S]) -> (&'a Command, usize) {
+    let mut leaf = command;
+    let mut end = 0;
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i].as_ref();
+        if word.starts_with('-') {
+            if find_option(leaf, word).is_some_and(|a| a.get_action().takes_values()) {
+                i += 1;
+            }
+        } else if let Some(sub) = leaf.find_subcommand(word) {
+            leaf = sub;
+            end = i + 1;
+        } else {
+            break;
+        }
+        i += 1;
+    }
+
+    (leaf, end)
+}
+
+fn find_option<'a>(command: &'a Command, word: &str) -> Option<&'a Arg> {
+    command.get_arguments().find(|arg| {
+        if let Some(long) = word.strip_prefix("--") {
+            arg.get_long() == Some(long)
+        } else {
+            let mut chars = word.chars().skip(1);
+            arg.get_short() == chars.next() && chars.next().is_none()
+        }
+    })
+}
+
+fn value_name(arg: &Arg) -> Option<&str> {
+    arg.get_value_names()
+        .and_then(|names| names.first())
+        .map(|name| name.as_str())
+}
+
+fn is_portfolio_arg(arg: &Arg) -> bool {
+    value_name(arg) == Some("PORTFOLIO_ID")
+}
+
+/// Whether the working portfolio is given for `arg` when it is missing. Lists of portfolios, like
+/// the one of `holdings`, mean all portfolios when empty.
+fn takes_working_portfolio(arg: &Arg) -> bool {
+    is_portfolio_arg(arg)
+        && arg.is_required_set()
+        && arg.get_num_args().is_none_or(|n| n.max_values() == 1)
+}
+
+#[derive(Helper, Hinter, Highlighter, Validator)]
+struct ShellHelper {
+    command: Command,
+    portfolios: Vec<Portfolio>,
+    fund_codes: Vec<(String, String)>,
+    working: Option<Portfolio>,
+}
+
+impl ShellHelper {
+    fn find_portfolio(&self, name_or_id: &str) -> Option<&Portfolio> {
+        self.portfolios
+            .iter()
+            .find(|p| p.name == name_or_id || p.id.to_string() == name_or_id)
+    }
+
+    fn prompt(&self) -> String {
+        match &self.working {
+            Some(portfolio) => format!("pfo ({})> ", portfolio.name),
+            None => "pfo> ".to_string(),
+        }
+    }
+
+    /// Replace a portfolio name with its id, or insert id of the working portfolio when a
+    /// command needs a single portfolio and none is given
+    fn expand(&self, mut words: Vec<String>) -> Vec<String> {
+        let (leaf, end) = find_leaf(&self.command, &words);
+        let Some(arg) = leaf
+            .get_positionals()
+            .next()
+            .filter(|arg| is_portfolio_arg(arg))
+        else {
+            return words;
+        };
+
+        match words.get(end) {
+            Some(word) if word.parse::<Uuid>().is_ok() => {}
+            Some(word) if self.find_portfolio(word).is_some() => {
+                words[end] = self.find_portfolio(word).unwrap().id.to_string();
+            }
+            _ if takes_working_portfolio(arg) => {
+                if let Some(portfolio) = &self.working {
+                    words.insert(end, portfolio.id.to_string());
+                }
+            }
+            _ => {}
+        }
+
+        words
+    }
+
+    fn values(&self, arg: &Arg, word: &str) -> Vec<Pair> {
+        match value_name(arg) {
+            Some("PORTFOLIO_ID") => self.portfolio_candidates(word),
+            Some("FUND_CODE" | "FUND_CODES") => self
+                .fund_codes
+                .iter()
+                .filter(|(code, _)| code.starts_with(&word.to_uppercase()))
+                .map(|(code, title)| Pair {
+                    display: format!("{} {}", code, title),
+                    replacement: code.clone(),
+                })
+                .collect(),
+            _ => arg
+                .get_possible_values()
+                .iter()
+                .map(|value| value.get_name())
+                .filter(|name| name.starts_with(word))
+                .map(|name| Pair {
+                    display: name.to_string(),
+                    replacement: name.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn portfolio_candidates(&self, word: &str) -> Vec<Pair> {
+        self.portfolios
+            .iter()
+            .filter(|p| p.name.starts_with(word) || p.id.to_string().starts_with(word))
+            .map(|p| Pair {
+                display: format!("{} ({})", p.name, p.id),
+                replacement: shlex::try_quote(&p.name)
+                    .map_or_else(|_| p.id.to_string(), |name| name.into_owned()),
+            })
+            .collect()
+    }
+
+    fn candidates(&self, words: &[&str], word: &str) -> Vec<Pair> {
+        let names = |names: Vec<String>| {
+            names
+                .into_iter()
+                .filter(|name| name.starts_with(word))
+                .map(|name| Pair {
+                    display: name.clone(),
+                    replacement: name,
+                })
+                .collect()
+        };
+
+        if words.first() == Some(&"use") {
+            return if words.len() == 1 {
+                self.portfolio_candidates(word)
+            } else {
+                Vec::new()
+            };
+        }
+
+        let (leaf, end) = find_leaf(&self.command, words);
+        let rest = &words[end..];
+
+        if let Some(previous) = rest.last().filter(|w| w.starts_with('-'))
+            && let Some(arg) = find_option(leaf, previous)
+            && arg.get_action().takes_values()
+        {
+            return self.values(arg, word);
+        }
+
+        if word.starts_with('-') {
+            return names(
+                leaf.get_arguments()
+                    .filter_map(|arg| arg.get_long())
+                    .map(|long| format!("--{}", long))
+                    .collect(),
+            );
+        }
+
+        if leaf.has_subcommands() {
+            let mut subcommands: Vec<String> = leaf
+                .get_subcommands()
+                .map(|c| c.get_name().to_string())
+                .collect();
+            if words.is_empty() {
+                subcommands.extend(BUILTINS.map(String::from));
+            }
+            return names(subcommands);
+        }
+
+        let mut given = Vec::new();
+        let mut i = 0;
+        while i < rest.len() {
+            if rest[i].starts_with('-') {
+                if find_option(leaf, rest[i]).is_some_and(|a| a.get_action().takes_values()) {
+                    i += 1;
+                }
+            } else {
+                given.push(rest[i]);
+            }
+            i += 1;
+        }
+
+        let mut positionals: Vec<&Arg> = leaf.get_positionals().collect();
+        if self.working.is_some()
+            && positionals
+                .first()
+                .is_some_and(|arg| takes_working_portfolio(arg))
+            && given
+                .first()
+                .is_none_or(|w| w.parse::<Uuid>().is_err() && self.find_portfolio(w).is_none())
+        {
+            // Working portfolio is inserted by expand, so given words are the following arguments
+            positionals.remove(0);
+        }
+
+        positionals
+            .get(given.len())
+            .or_else(|| {
+                positionals
+                    .last()
+                    .filter(|arg| arg.get_num_args().is_some_and(|n| n.max_values() > 1))
+            })
+            .map(|arg| self.values(arg, word))
+            .unwrap_or_default()
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        let start = before.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let words: Vec<&str> = before[..start].split_whitespace().collect();
+
+        Ok((start, self.candidates(&words, &before[start..])))
+    }
+}
+
+fn history_path(database: Option<&Path>) -> Result<PathBuf> {
+    let database = match database {
+        Some(database) => database.to_path_buf(),
+        None => Store::default_path()?,
+    };
+
+    Ok(database.with_file_name("shell_history"))
+}
+
+/// Interactive shell running subcommands of [`Args`] with a single client
+pub struct Shell {
+    client: PfoClient,
+    database: Option<PathBuf>,
+    options: UpdateOptions,
+    format: ErrorFormat,
+    editor: Editor<ShellHelper, DefaultHistory>,
+}
+
+impl Shell {
+    pub async fn new(
+        client: PfoClient,
+        database: Option<PathBuf>,
+        options: UpdateOptions,
+        format: ErrorFormat,
+    ) -> Result<Self> {
+        let mut command = Args::command();
+        command.build();
+
+        let fund_codes = match Store::open(database.as_deref())
+            .and_then(|store| store.get_funds(&[], None, None))
+        {
+            Ok(funds) => funds.into_iter().map(|f| (f.code, f.title)).collect(),
+            Err(err) => {
+                log::warn!("Fund codes of local archive are not completed: {:#}", err);
+                Vec::new()
+            }
+        };
+
+        let config = Config::builder()
+            .completion_type(CompletionType::List)
+            .auto_add_history(true)
+            .build();
+        let mut editor = Editor::with_config(config).context("Failed to initialize line editor")?;
+        editor.set_helper(Some(ShellHelper {
+            command,
+            portfolios: client.list_portfolios().await?,
+            fund_codes,
+            working: None,
+        }));
+
+        Ok(Self {
+            client,
+            database,
+            options,
+            format,
+            editor,
+        })
+    }
+
+    fn helper(&self) -> &ShellHelper {
+        self.editor.helper().expect("helper is set on creation")
+    }
+
+    fn helper_mut(&mut self) -> &mut ShellHelper {
+        self.editor.helper_mut().expect("helper is set on creation")
+    }
+
+    /// Set working portfolio, adding its funds to completed fund codes
+    async fn use_portfolio(&mut self, name_or_id: Option<&str>) -> Result<()> {
+        let Some(name_or_id) = name_or_id else {
+            self.helper_mut().working = None;
+            return Ok(());
+        };
+
+        let portfolios = self.client.list_portfolios().await?;
+        self.helper_mut().portfolios = portfolios;
+        let Some(portfolio) = self.helper().find_portfolio(name_or_id) else {
+            bail!(PfoError::NotFound(format!(
+                "No portfolio named {}",
+                name_or_id
+            )));
+        };
+        let (id, name) = (portfolio.id, portfolio.name.clone());
+
+        let prices = self
+            .client
+            .get_portfolio_fund_prices(id, None, None)
+            .await?;
+        let helper = self.helper_mut();
+        for price in prices {
+            if !helper
+                .fund_codes
+                .iter()
+                .any(|(code, _)| *code == price.code)
+            {
+                helper.fund_codes.push((price.code, price.title));
+            }
+        }
+        helper.fund_codes.sort();
+        helper.working = Some(Portfolio { id, name });
+
+        Ok(())
+    }
+
+    async fn execute(&mut self, words: Vec<String>) -> Result<()> {
+        let words = self.helper().expand(words);
+        let mut command = Args::command();
+        let parsed = command
+            .try_get_matches_from_mut(std::iter::once("pfo".to_string()).chain(words))
+            .and_then(|matches| Ok((Args::from_arg_matches(&matches)?, matches)));
+        let (args, matches) = match parsed {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                err.print()?;
+                return Ok(());
+            }
+        };
+
+        if let Some(arg) = command.get_arguments().find(|arg| {
+            arg.is_global_set()
+                && !SHELL_GLOBALS.contains(&arg.get_id().as_str())
+                && matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+        }) {
+            bail!(
+                "--{} can not be changed in shell, restart it with the option instead",
+                arg.get_long().unwrap_or_default()
+            );
+        }
+        if matches!(args.command, Commands::Shell) {
+            bail!("Already in shell");
+        }
+
+        let options = UpdateOptions {
+            dry_run: self.options.dry_run || args.dry_run,
+            yes: self.options.yes || args.yes,
+        };
+        let database = args.database.or_else(|| self.database.clone());
+
+        Box::pin(
+            args.command
+                .handle(self.client.clone(), database, options, self.format),
+        )
+        .await
+    }
+
+    /// Read and run commands until `exit` or end of input
+    pub async fn run(mut self) -> Result<()> {
+        let history = history_path(self.database.as_deref())?;
+        if self.editor.load_history(&history).is_err() {
+            log::debug!("No shell history at {}", history.display());
+        }
+
+        loop {
+            let prompt = self.helper().prompt();
+            let line = match self.editor.readline(&prompt) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(err) => return Err(err).context("Failed to read line"),
+            };
+
+            let Some(words) = shlex::split(&line) else {
+                let err = anyhow!("Unbalanced quotes in {}", line);
+                error::print(&err, self.format);
+                continue;
+            };
+            // Blank lines and comments have no words
+            let Some(first) = words.first() else {
+                continue;
+            };
+            let result = match first.as_str() {
+                "exit" | "quit" => break,
+                "use" => self.use_portfolio(words.get(1).map(String::as_str)).await,
+                _ => self.execute(words).await,
+            };
+            if let Err(err) = result {
+                error::print(&err, self.format);
+            }
+        }
+
+        self.editor.save_history(&history).context(format!(
+            "Failed to save shell history to {}",
+            history.display()
+        ))
+    }
+}
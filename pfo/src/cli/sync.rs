@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use pfo_client::PfoClient;
+
+use crate::cli::FundFilterArgs;
+use crate::store::Store;
+
+#[derive(Args)]
+pub struct SyncArgs {
+    #[command(flatten)]
+    fund_filter: FundFilterArgs,
+}
+
+impl SyncArgs {
+    pub async fn handle(self, client: PfoClient, database: Option<PathBuf>) -> Result<()> {
+        let mut store = Store::open(database.as_deref())?;
+        let codes = self.fund_filter.codes.clone();
+
+        let funds = client
+            .get_funds(self.fund_filter.into(), None)
+            .await
+            .context("Failed to fetch fund informations")?;
+        let stats = client
+            .get_fund_price_stats(codes, None)
+            .await
+            .context("Failed to fetch fund price stats")?;
+
+        let archived_funds = store.archive_funds(&funds)?;
+        let archived_stats = store.archive_fund_price_stats(&stats)?;
+
+        println!(
+            "Archived {} fund informations and {} fund price stats",
+            archived_funds, archived_stats
+        );
+
+        Ok(())
+    }
+}
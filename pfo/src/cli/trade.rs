@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use chrono::{Local, NaiveDate};
+use clap::Args;
+use pfo_client::PfoClient;
+use pfo_core::parse_naive_date;
+use uuid::Uuid;
+
+use crate::cli::update::{UpdateOptions, review_update, send_update};
+use crate::error::PfoError;
+use crate::portfolio::{PortfolioFundUpdate, PortfolioUpdate, TransactionKind, TransactionRecord};
+use crate::store::Store;
+
+#[derive(Args)]
+pub struct TradeArgs {
+    #[arg(value_name = "PORTFOLIO_ID", help = "Portfolio UUID")]
+    id: Uuid,
+
+    #[arg(value_name = "FUND_CODE", help = "Fund code to trade")]
+    code: String,
+
+    #[arg(
+        short,
+        long,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Number of units, at least 1"
+    )]
+    units: u32,
+
+    #[arg(
+        short = 'P',
+        long,
+        help = "Price of a single unit. Latest price known by server is used if omitted"
+    )]
+    unit_price: Option<f64>,
+
+    #[arg(
+        short,
+        long,
+        default_value_t = 0.0,
+        help = "Fees paid for the transaction"
+    )]
+    fees: f64,
+
+    #[arg(
+        short,
+        long,
+        value_parser = parse_naive_date,
+        help = "Date of the transaction. Today is used if omitted"
+    )]
+    date: Option<NaiveDate>,
+}
+
+impl TradeArgs {
+    /// Record the trade in the ledger and update owned amount and money spent of the fund.
+    ///
+    /// Money spent is reduced by the average cost of sold units, fees of a sell are not part of
+    /// the cost.
+    pub async fn handle(
+        self,
+        kind: TransactionKind,
+        client: PfoClient,
+        database: Option<PathBuf>,
+        options: UpdateOptions,
+    ) -> Result<()> {
+        let mut store = Store::open(database.as_deref())?;
+
+        let current = client
+            .get_portfolio_fund_prices(self.id, None, None)
+            .await?
+            .into_iter()
+            .find(|p| p.code == self.code);
+
+        let (owned, spent) = current
+            .as_ref()
+            .map_or((0, 0.0), |p| (p.owned_amount, p.money_spent));
+        let unit_price = match (self.unit_price, &current) {
+            (Some(unit_price), _) => unit_price,
+            (None, Some(current)) => current.price,
+            (None, None) => bail!(PfoError::NotFound(format!(
+                "Fund {} is not in portfolio {}, give the price with --unit-price",
+                self.code, self.id
+            ))),
+        };
+
+        let (new_owned, new_spent) = match kind {
+            TransactionKind::Buy => (
+                owned + self.units,
+                spent + self.units as f64 * unit_price + self.fees,
+            ),
+            TransactionKind::Sell => {
+                if owned == 0 {
+                    bail!(PfoError::Validation(format!(
+                        "Cannot sell {}, no units are owned",
+                        self.code
+                    )));
+                }
+                if self.units > owned {
+                    bail!(PfoError::Validation(format!(
+                        "Cannot sell {} units of {}, only {} units are owned",
+                        self.units, self.code, owned
+                    )));
+                }
+
+                (
+                    owned - self.units,
+                    spent - spent * self.units as f64 / owned as f64,
+                )
+            }
+        };
+
+        let record = TransactionRecord {
+            code: self.code.clone(),
+            date: self.date.unwrap_or_else(|| Local::now().date_naive()),
+            kind,
+            units: self.units,
+            unit_price,
+            fees: self.fees,
+        };
+        let update = PortfolioUpdate {
+            add_codes: HashSet::from([PortfolioFundUpdate {
+                fund_code: self.code.clone(),
+                weight: None,
+                min_amount: None,
+                owned_amount: Some(new_owned),
+                total_money_spent: Some(new_spent),
+            }]),
+            remove_codes: HashSet::new(),
+        };
+
+        if !review_update(&client, self.id, &update, options).await? {
+            return Ok(());
+        }
+
+        let transaction_id = store.add_transactions(self.id, &[record])?[0];
+        if let Err(err) = send_update(&client, &mut store, self.id, update, &[transaction_id]).await
+        {
+            if let Err(rollback) = store.remove_transaction(self.id, transaction_id) {
+                log::error!(
+                    "Failed to remove transaction {} of the failed update: {:#}",
+                    transaction_id,
+                    rollback
+                );
+            }
+            return Err(err).context(format!("Failed to {} {}", kind, self.code));
+        }
+
+        println!(
+            "Successfully recorded {} {} of {} at {:.6}",
+            kind, self.units, self.code, unit_price
+        );
+        println!("Owned: {} -> {}", owned, new_owned);
+        println!("Money spent: {:.6} -> {:.6}", spent, new_spent);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::{Context, Result};
+use pfo_client::PfoClient;
+use uuid::Uuid;
+
+use crate::cli::prompt::confirm;
+use crate::portfolio::{PortfolioFundPrice, PortfolioFundUpdate, PortfolioUpdate};
+use crate::store::Store;
+
+/// Global options of commands that update a portfolio
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UpdateOptions {
+    pub dry_run: bool,
+    pub yes: bool,
+}
+
+fn describe_change<T: PartialEq + ToString>(
+    changes: &mut Vec<String>,
+    name: &str,
+    before: Option<T>,
+    after: Option<T>,
+) {
+    match (before, after) {
+        (Some(before), Some(after)) if before != after => changes.push(format!(
+            "{}: {} -> {}",
+            name,
+            before.to_string(),
+            after.to_string()
+        )),
+        (None, Some(after)) => changes.push(format!("{}: {}", name, after.to_string())),
+        _ => {}
+    }
+}
+
+fn describe_fund_update(
+    fund: &PortfolioFundUpdate,
+    current: Option<&PortfolioFundPrice>,
+) -> String {
+    let mut changes = Vec::with_capacity(4);
+    if let Some(weight) = fund.weight {
+        changes.push(match current {
+            Some(current) => format!(
+                "weight: {:.2} (normalized) -> {}",
+                current.normalized_weight, weight
+            ),
+            None => format!("weight: {}", weight),
+        });
+    }
+    describe_change(
+        &mut changes,
+        "min amount",
+        current.map(|c| c.min_amount),
+        fund.min_amount,
+    );
+    describe_change(
+        &mut changes,
+        "owned",
+        current.map(|c| c.owned_amount),
+        fund.owned_amount,
+    );
+    describe_change(
+        &mut changes,
+        "money spent",
+        current.map(|c| format!("{:.6}", c.money_spent)),
+        fund.total_money_spent.map(|s| format!("{:.6}", s)),
+    );
+
+    let sign = if current.is_some() { '~' } else { '+' };
+    if changes.is_empty() {
+        format!("{} {} (no changes)", sign, fund.fund_code)
+    } else {
+        format!("{} {} {}", sign, fund.fund_code, changes.join(", "))
+    }
+}
+
+fn describe_removal(code: &str, current: Option<&PortfolioFundPrice>) -> String {
+    match current {
+        Some(current) => format!(
+            "- {} owned: {}, money spent: {:.6}",
+            code, current.owned_amount, current.money_spent
+        ),
+        None => format!("- {} (not in portfolio)", code),
+    }
+}
+
+/// Lines of a diff of `update` against current state of the portfolio
+fn diff(update: &PortfolioUpdate, prices: &[PortfolioFundPrice]) -> Vec<String> {
+    let find = |code: &str| prices.iter().find(|p| p.code == code);
+
+    let mut lines: Vec<(&str, String)> = update
+        .add_codes
+        .iter()
+        .map(|f| {
+            (
+                f.fund_code.as_str(),
+                describe_fund_update(f, find(&f.fund_code)),
+            )
+        })
+        .chain(
+            update
+                .remove_codes
+                .iter()
+                .map(|c| (c.as_str(), describe_removal(c, find(c)))),
+        )
+        .collect();
+    lines.sort();
+
+    lines.into_iter().map(|(_, line)| line).collect()
+}
+
+/// What to do with an update, decided by [`check_update`]
+pub enum UpdateReview {
+    /// Send the update
+    Send,
+    /// Do not send the update, show its JSON and diff against current state instead
+    Preview(String),
+    /// Send the update only if the user confirms removal of funds with these descriptions
+    Confirm(Vec<String>),
+}
+
+/// Decide whether `update` should be sent, without printing or asking anything.
+///
+/// With `--dry-run` nothing is sent and the update is previewed as JSON along with a diff against
+/// current fund prices of the portfolio. Removing funds that still have owned units requires a
+/// confirmation unless `--yes` is given.
+pub async fn check_update(
+    client: &PfoClient,
+    id: Uuid,
+    update: &PortfolioUpdate,
+    options: UpdateOptions,
+) -> Result<UpdateReview> {
+    if !options.dry_run && (options.yes || update.remove_codes.is_empty()) {
+        return Ok(UpdateReview::Send);
+    }
+
+    let prices = client.get_portfolio_fund_prices(id, None, None).await?;
+
+    if options.dry_run {
+        let mut lines =
+            vec![serde_json::to_string_pretty(update).context("Failed to serialize update")?];
+        lines.extend(diff(update, &prices));
+        return Ok(UpdateReview::Preview(lines.join("\n")));
+    }
+
+    let removals: Vec<String> = prices
+        .iter()
+        .filter(|p| p.owned_amount > 0 && update.remove_codes.contains(&p.code))
+        .map(|p| describe_removal(&p.code, Some(p)))
+        .collect();
+    if removals.is_empty() {
+        Ok(UpdateReview::Send)
+    } else {
+        Ok(UpdateReview::Confirm(removals))
+    }
+}
+
+/// Show what `update` would change and decide whether it should be sent, see [`check_update`].
+/// Removals are confirmed on the terminal.
+pub async fn review_update(
+    client: &PfoClient,
+    id: Uuid,
+    update: &PortfolioUpdate,
+    options: UpdateOptions,
+) -> Result<bool> {
+    match check_update(client, id, update, options).await? {
+        UpdateReview::Send => Ok(true),
+        UpdateReview::Preview(preview) => {
+            println!("{}", preview);
+            Ok(false)
+        }
+        UpdateReview::Confirm(removals) => {
+            for removal in removals {
+                println!("{}", removal);
+            }
+            if confirm("Remove funds that still have owned units?")? {
+                Ok(true)
+            } else {
+                println!("Aborted");
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// Send `update` to the server after saving current funds of the portfolio in the local journal,
+/// so that it can be reverted with `portfolio undo`. Ledger transactions `transaction_ids` recorded
+/// for the update are removed when it is reverted.
+pub async fn send_update(
+    client: &PfoClient,
+    store: &mut Store,
+    id: Uuid,
+    update: PortfolioUpdate,
+    transaction_ids: &[u32],
+) -> Result<()> {
+    let prices = client.get_portfolio_fund_prices(id, None, None).await?;
+    let entry_id = store.add_journal_entry(id, &update, &prices, transaction_ids)?;
+
+    if let Err(err) = client.update_portfolio(id, update).await {
+        store.remove_journal_entry(entry_id)?;
+        return Err(err.into());
+    }
+
+    Ok(())
+}
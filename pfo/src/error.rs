@@ -0,0 +1,155 @@
+use std::process::ExitCode;
+
+use clap::ValueEnum;
+use pfo_client::Error as ClientError;
+use reqwest::{Method, Url};
+use serde::Serialize;
+
+/// Failures of `pfo` commands, told apart by exit code together with [`pfo_client::Error`] of
+/// requests
+#[derive(Debug, thiserror::Error)]
+pub enum PfoError {
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    OutOfBand(String),
+}
+
+impl PfoError {
+    /// Process exit code, see [`exit_code`]
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::NotFound(_) => 6,
+            Self::Validation(_) => 8,
+            Self::OutOfBand(_) => 9,
+        }
+    }
+}
+
+/// Process exit code of a failed request, see [`exit_code`]
+fn client_exit_code(err: &ClientError) -> u8 {
+    match err {
+        ClientError::Transport { .. } => 3,
+        ClientError::Timeout { .. } => 4,
+        ClientError::Status { problem, .. } if problem.status == 404 => 6,
+        ClientError::Status { .. } => 5,
+        ClientError::Decode { .. } => 7,
+        _ => 1,
+    }
+}
+
+/// First [`PfoError`], [`pfo_client::Error`] or [`clap::Error`] in the chain of `err`
+fn find(err: &anyhow::Error) -> Option<Found<'_>> {
+    err.chain().find_map(|e| {
+        e.downcast_ref::<PfoError>()
+            .map(Found::Pfo)
+            .or_else(|| e.downcast_ref::<ClientError>().map(Found::Client))
+            .or_else(|| e.downcast_ref::<clap::Error>().map(Found::Args))
+    })
+}
+
+enum Found<'a> {
+    Pfo(&'a PfoError),
+    Client(&'a ClientError),
+    /// Arguments rejected by a command after parsing, e.g. when they depend on each other
+    Args(&'a clap::Error),
+}
+
+impl Found<'_> {
+    fn exit_code(&self) -> u8 {
+        match self {
+            Self::Pfo(err) => err.exit_code(),
+            Self::Client(err) => client_exit_code(err),
+            Self::Args(err) => err.exit_code() as u8,
+        }
+    }
+}
+
+/// Exit code for `err`, taken from the first [`PfoError`], [`pfo_client::Error`] or
+/// [`clap::Error`] in its chain. Otherwise failed commands exit with 1.
+pub fn exit_code(err: &anyhow::Error) -> ExitCode {
+    find(err).map_or(ExitCode::FAILURE, |e| ExitCode::from(e.exit_code()))
+}
+
+/// Exit codes listed in help of `pfo`
+pub const EXIT_CODES: &str = "Exit codes:
+  0  Success
+  1  Any other failure
+  2  Invalid command line arguments
+  3  Server could not be reached
+  4  Request timed out
+  5  Server responded with an error status
+  6  Portfolio, fund or other requested item was not found (including HTTP 404)
+  7  Response could not be decoded
+  8  Request was rejected before sending, e.g. selling more units than owned
+  9  Funds are out of tolerance band in `portfolio drift`";
+
+/// How failures are printed on stderr
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum ErrorFormat {
+    /// Message with its causes
+    #[default]
+    Text,
+    /// JSON object with ProblemDetail fields of the server response, request method and URL
+    Json,
+}
+
+/// Error printed with [`ErrorFormat::Json`]. ProblemDetail fields are null for errors that did not
+/// come from a server response.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ErrorReport<'a> {
+    #[serde(rename = "type")]
+    problem_type: Option<&'a str>,
+    title: Option<&'a str>,
+    status: Option<u16>,
+    detail: Option<&'a str>,
+    instance: Option<&'a str>,
+    response_body: Option<&'a str>,
+    method: Option<String>,
+    url: Option<String>,
+    message: String,
+    exit_code: u8,
+}
+
+/// Print `err` on stderr in `format`
+pub fn print(err: &anyhow::Error, format: ErrorFormat) {
+    match format {
+        // Printed like errors of parsing, with usage
+        ErrorFormat::Text if let Some(args_err) = err.downcast_ref::<clap::Error>() => {
+            let _ = args_err.print();
+        }
+        ErrorFormat::Text => eprintln!("Error: {:?}", err),
+        ErrorFormat::Json => {
+            let found = find(err);
+            let client_error = match &found {
+                Some(Found::Client(err)) => Some(*err),
+                _ => None,
+            };
+            let problem = client_error.and_then(ClientError::problem);
+            let report = ErrorReport {
+                problem_type: problem.and_then(|p| p.problem_type.as_deref()),
+                title: problem.and_then(|p| p.title.as_deref()),
+                status: problem.map(|p| p.status),
+                detail: problem.and_then(|p| p.detail.as_deref()),
+                instance: problem.and_then(|p| p.instance.as_deref()),
+                response_body: problem.and_then(|p| p.response_body.as_deref()),
+                method: client_error
+                    .and_then(ClientError::method)
+                    .map(Method::to_string),
+                url: client_error.and_then(ClientError::url).map(Url::to_string),
+                message: format!("{:#}", err),
+                exit_code: found.map_or(1, |e| e.exit_code()),
+            };
+
+            match serde_json::to_string(&report) {
+                Ok(json) => eprintln!("{}", json),
+                Err(_) => eprintln!("Error: {:?}", err),
+            }
+        }
+    }
+}
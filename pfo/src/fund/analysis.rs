@@ -0,0 +1,78 @@
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use pfo_core::impl_table;
+use pfo_derive::OutputTable;
+
+use crate::analytics::PriceSeries;
+
+/// Return statistics of a fund computed locally from its price history
+#[derive(Debug, OutputTable)]
+pub struct FundAnalysis {
+    #[column(max_width = 3, is_default)]
+    pub code: String,
+
+    #[column(max_width = 10, is_default)]
+    pub from: Option<NaiveDate>,
+
+    #[column(max_width = 10, is_default)]
+    pub to: Option<NaiveDate>,
+
+    #[column(header = "Prices", max_width = 10, left_align = false)]
+    pub observations: u32,
+
+    #[column(max_width = 30, left_align = false, is_default)]
+    pub total_return: Option<f64>,
+
+    #[column(header = "CAGR", max_width = 30, left_align = false, is_default)]
+    pub cagr: Option<f64>,
+
+    #[column(max_width = 30, left_align = false, is_default)]
+    pub volatility: Option<f64>,
+
+    #[column(max_width = 30, left_align = false, is_default)]
+    pub max_drawdown: Option<f64>,
+
+    #[column(max_width = 30, left_align = false, is_default)]
+    pub sharpe: Option<f64>,
+
+    #[column(max_width = 30, left_align = false, is_default)]
+    pub sortino: Option<f64>,
+}
+
+impl_table!(FundAnalysis, FundAnalysisColumn, FundAnalysisRow);
+
+impl FundAnalysis {
+    pub fn new(code: String, series: &PriceSeries, risk_free_rate: f64) -> Self {
+        Self {
+            code,
+            from: series.first().map(|(date, _)| date),
+            to: series.last().map(|(date, _)| date),
+            observations: series.len() as u32,
+            total_return: series.total_return(),
+            cagr: series.cagr(),
+            volatility: series.volatility(),
+            max_drawdown: series.max_drawdown(),
+            sharpe: series.sharpe_ratio(risk_free_rate),
+            sortino: series.sortino_ratio(risk_free_rate),
+        }
+    }
+}
+
+/// Return of a fund over the last `days` days of its price history
+#[derive(Debug, OutputTable)]
+pub struct FundWindowReturn {
+    #[column(max_width = 3, is_default)]
+    pub code: String,
+
+    #[column(max_width = 10, is_default, left_align = false)]
+    pub days: u32,
+
+    #[column(header = "Return", max_width = 30, left_align = false, is_default)]
+    pub window_return: Option<f64>,
+}
+
+impl_table!(
+    FundWindowReturn,
+    FundWindowReturnColumn,
+    FundWindowReturnRow
+);
@@ -1,5 +1,5 @@
-mod info;
-mod price_stats;
+mod analysis;
 
-pub use info::{FundInfo, FundInfoColumn};
-pub use price_stats::{FundPriceStats, FundPriceStatsColumn};
+pub use pfo_client::{FundInfo, FundInfoColumn, FundPriceStats, FundPriceStatsColumn};
+
+pub use analysis::{FundAnalysis, FundAnalysisColumn, FundWindowReturn};
@@ -1,28 +1,70 @@
+mod analytics;
 mod cli;
-mod client;
+mod error;
 mod fund;
-mod none_serialize;
 mod portfolio;
-mod problem_detail;
-mod query;
+mod store;
+mod tui;
+
+use std::process::ExitCode;
+use std::time::Duration;
 
 use anyhow::Result;
 use clap::Parser;
+use clap_complete::CompleteEnv;
 use cli::Args;
+use pfo_client::PfoClient;
+
+fn main() -> ExitCode {
+    // Completions are served before starting the runtime, since completers fetch candidates with
+    // their own
+    CompleteEnv::with_factory(cli::completion_command).complete();
 
-#[tokio::main]
-async fn main() -> Result<()> {
     let env = env_logger::Env::default()
         .filter_or("PFO_LOG_LEVEL", "info")
         .write_style_or("PFO_LOG_STYLE", "always");
     env_logger::init_from_env(env);
 
     let args = Args::parse();
+    let format = args.format;
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            error::print(&err, format);
+            error::exit_code(&err)
+        }
+    }
+}
+
+#[tokio::main]
+async fn run(args: Args) -> Result<()> {
+    let mut builder = PfoClient::builder()
+        .host(args.host.unwrap_or("localhost".into()))
+        .port(args.port.unwrap_or(8080));
+    if let Some(timeout) = args.connect_timeout {
+        builder = builder.connect_timeout(Duration::from_secs(timeout));
+    }
+    if let Some(timeout) = args.read_timeout {
+        builder = builder.read_timeout(Duration::from_secs(timeout));
+    }
+    if let Some(retries) = args.retries {
+        builder = builder.retries(retries);
+    }
+    if let Some(max_in_flight) = args.max_in_flight {
+        builder = builder.max_in_flight(max_in_flight);
+    }
+    if let Some(rate_limit) = args.rate_limit {
+        builder = builder.requests_per_second(rate_limit);
+    }
+    let client = builder.build()?;
 
-    let client = client::PfoClient::new(
-        args.host.unwrap_or("localhost".into()),
-        args.port.unwrap_or(8080),
-    )?;
+    let options = cli::UpdateOptions {
+        dry_run: args.dry_run,
+        yes: args.yes,
+    };
 
-    args.command.handle(client).await
+    args.command
+        .handle(client, args.database, options, args.format)
+        .await
 }
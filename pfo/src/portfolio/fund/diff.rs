@@ -0,0 +1,108 @@
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use pfo_core::impl_table;
+use pfo_derive::OutputTable;
+
+use crate::portfolio::PortfolioFundPrice;
+
+/// Change of a fund in a portfolio between two points in time
+#[derive(Debug, OutputTable)]
+pub struct PortfolioFundDiff {
+    #[column(max_width = 3, is_default)]
+    pub code: String,
+
+    #[column(max_width = 25)]
+    pub title: String,
+
+    #[column(max_width = 9, is_default)]
+    pub status: String,
+
+    #[column(max_width = 10)]
+    pub from_date: Option<NaiveDate>,
+
+    #[column(max_width = 10)]
+    pub to_date: Option<NaiveDate>,
+
+    #[column(max_width = 10, is_default, left_align = false)]
+    pub owned_change: i64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub spent_change: f64,
+
+    #[column(max_width = 30, left_align = false)]
+    pub from_price: Option<f64>,
+
+    #[column(max_width = 30, left_align = false)]
+    pub to_price: Option<f64>,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub price_change: Option<f64>,
+
+    #[column(max_width = 30, left_align = false)]
+    pub from_value: f64,
+
+    #[column(max_width = 30, left_align = false)]
+    pub to_value: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub value_change: f64,
+}
+
+impl_table!(
+    PortfolioFundDiff,
+    PortfolioFundDiffColumn,
+    PortfolioFundDiffRow
+);
+
+impl PortfolioFundDiff {
+    fn new(from: Option<&PortfolioFundPrice>, to: Option<&PortfolioFundPrice>) -> Self {
+        let owned = |p: Option<&PortfolioFundPrice>| p.map_or(0, |p| i64::from(p.owned_amount));
+        let spent = |p: Option<&PortfolioFundPrice>| p.map_or(0.0, |p| p.money_spent);
+        let value =
+            |p: Option<&PortfolioFundPrice>| p.map_or(0.0, |p| p.price * p.owned_amount as f64);
+
+        let (from_price, to_price) = (from.map(|p| p.price), to.map(|p| p.price));
+        let owned_change = owned(to) - owned(from);
+        let spent_change = spent(to) - spent(from);
+        let status = match (from, to) {
+            (None, _) => "added",
+            (_, None) => "removed",
+            _ if owned_change != 0 || spent_change != 0.0 => "changed",
+            _ => "unchanged",
+        };
+        let latest = to.or(from).expect("fund exists on at least one side");
+
+        Self {
+            code: latest.code.clone(),
+            title: latest.title.clone(),
+            status: status.to_string(),
+            from_date: from.map(|p| p.date),
+            to_date: to.map(|p| p.date),
+            owned_change,
+            spent_change,
+            from_price,
+            to_price,
+            price_change: from_price.zip(to_price).map(|(f, t)| t - f),
+            from_value: value(from),
+            to_value: value(to),
+            value_change: value(to) - value(from),
+        }
+    }
+
+    /// Changes of every fund that is in `from` or `to`, ordered by fund code
+    pub fn between(from: &[PortfolioFundPrice], to: &[PortfolioFundPrice]) -> Vec<Self> {
+        let mut codes: Vec<&str> = from.iter().chain(to).map(|p| p.code.as_str()).collect();
+        codes.sort();
+        codes.dedup();
+
+        codes
+            .into_iter()
+            .map(|code| {
+                Self::new(
+                    from.iter().find(|p| p.code == code),
+                    to.iter().find(|p| p.code == code),
+                )
+            })
+            .collect()
+    }
+}
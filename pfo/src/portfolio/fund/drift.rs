@@ -0,0 +1,174 @@
+use clap::ValueEnum;
+use pfo_core::impl_table;
+use pfo_derive::OutputTable;
+
+use crate::portfolio::PortfolioFundPrice;
+
+/// Drift within this of a tolerance is in band, so that rounding of weights and values does not
+/// push funds exactly at the tolerance out of it
+const EPSILON: f64 = 1e-9;
+
+/// Difference between the share of a fund in portfolio value and its normalized weight
+#[derive(Debug, OutputTable)]
+pub struct PortfolioFundDrift {
+    #[column(max_width = 3, is_default)]
+    pub code: String,
+
+    #[column(max_width = 25)]
+    pub title: String,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub value: f64,
+
+    #[column(header = "Target", max_width = 15, is_default)]
+    pub normalized_weight: f32,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub weight: Option<f64>,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub drift: Option<f64>,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub relative_drift: Option<f64>,
+
+    #[column(header = "Out Of Band", max_width = 5, is_default)]
+    pub out_of_band: bool,
+}
+
+impl_table!(
+    PortfolioFundDrift,
+    PortfolioFundDriftColumn,
+    PortfolioFundDriftRow
+);
+
+impl PortfolioFundDrift {
+    /// Compute drift of each fund from `price` × `owned_amount`.
+    ///
+    /// A fund is out of band when its drift exceeds `tolerance` or, if given, its drift relative to
+    /// its target exceeds `relative_tolerance`. Drift exactly at a tolerance is in band.
+    pub fn from_prices(
+        prices: &[PortfolioFundPrice],
+        tolerance: f64,
+        relative_tolerance: Option<f64>,
+    ) -> Vec<Self> {
+        let total: f64 = prices.iter().map(|p| p.price * p.owned_amount as f64).sum();
+
+        prices
+            .iter()
+            .map(|p| {
+                let value = p.price * p.owned_amount as f64;
+                let target = p.normalized_weight as f64;
+                let weight = (total > 0.0).then(|| value / total);
+                let drift = weight.map(|w| w - target);
+                let relative_drift = drift.filter(|_| target > 0.0).map(|d| d / target);
+
+                let out_of_band = drift.is_some_and(|d| d.abs() > tolerance + EPSILON)
+                    || relative_drift
+                        .zip(relative_tolerance)
+                        .is_some_and(|(d, t)| d.abs() > t + EPSILON);
+
+                Self {
+                    code: p.code.clone(),
+                    title: p.title.clone(),
+                    value,
+                    normalized_weight: p.normalized_weight,
+                    weight,
+                    drift,
+                    relative_drift,
+                    out_of_band,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn price(
+        code: &str,
+        price: f64,
+        normalized_weight: f32,
+        owned_amount: u32,
+    ) -> PortfolioFundPrice {
+        PortfolioFundPrice {
+            portfolio_id: Uuid::nil(),
+            code: code.to_string(),
+            title: code.to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            price,
+            normalized_weight,
+            min_amount: 0,
+            owned_amount,
+            money_spent: 0.0,
+        }
+    }
+
+    fn out_of_band(drifts: &[PortfolioFundDrift]) -> Vec<&str> {
+        drifts
+            .iter()
+            .filter(|d| d.out_of_band)
+            .map(|d| d.code.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn drift_is_share_of_value_minus_weight() {
+        let prices = [price("AAA", 10.0, 0.5, 6), price("BBB", 5.0, 0.5, 8)];
+
+        let drifts = PortfolioFundDrift::from_prices(&prices, 0.05, None);
+
+        assert_eq!(drifts[0].value, 60.0);
+        assert_eq!(drifts[0].weight, Some(0.6));
+        assert!((drifts[0].drift.unwrap() - 0.1).abs() < 1e-9);
+        assert!((drifts[0].relative_drift.unwrap() - 0.2).abs() < 1e-9);
+        assert!((drifts[1].drift.unwrap() + 0.1).abs() < 1e-9);
+        assert_eq!(out_of_band(&drifts), ["AAA", "BBB"]);
+    }
+
+    #[test]
+    fn zero_total_value_has_no_drift() {
+        let prices = [price("AAA", 10.0, 0.5, 0), price("BBB", 5.0, 0.5, 0)];
+
+        let drifts = PortfolioFundDrift::from_prices(&prices, 0.0, Some(0.0));
+
+        for drift in &drifts {
+            assert_eq!(drift.value, 0.0);
+            assert_eq!(drift.weight, None);
+            assert_eq!(drift.drift, None);
+            assert_eq!(drift.relative_drift, None);
+        }
+        assert!(out_of_band(&drifts).is_empty());
+    }
+
+    #[test]
+    fn drift_at_tolerance_is_in_band() {
+        let prices = [price("AAA", 1.0, 0.5, 55), price("BBB", 1.0, 0.5, 45)];
+
+        assert!(out_of_band(&PortfolioFundDrift::from_prices(&prices, 0.05, None)).is_empty());
+        assert!(out_of_band(&PortfolioFundDrift::from_prices(&prices, 0.05, Some(0.1))).is_empty());
+        assert_eq!(
+            out_of_band(&PortfolioFundDrift::from_prices(&prices, 0.049, None)),
+            ["AAA", "BBB"]
+        );
+        assert_eq!(
+            out_of_band(&PortfolioFundDrift::from_prices(&prices, 0.05, Some(0.099))),
+            ["AAA", "BBB"]
+        );
+    }
+
+    #[test]
+    fn relative_drift_needs_a_target() {
+        let prices = [price("AAA", 1.0, 1.0, 90), price("BBB", 1.0, 0.0, 10)];
+
+        let drifts = PortfolioFundDrift::from_prices(&prices, 0.2, Some(0.0));
+
+        assert_eq!(drifts[1].relative_drift, None);
+        assert_eq!(out_of_band(&drifts), ["AAA"]);
+    }
+}
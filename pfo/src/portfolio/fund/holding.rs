@@ -0,0 +1,64 @@
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use pfo_core::impl_table;
+use pfo_derive::OutputTable;
+use uuid::Uuid;
+
+use crate::portfolio::{Portfolio, PortfolioFundPrice};
+
+/// Fund held in one of several portfolios listed together
+#[derive(Debug, OutputTable)]
+pub struct PortfolioHolding {
+    #[column(max_width = 20, is_default)]
+    pub portfolio: String,
+
+    #[column(max_width = 36)]
+    pub portfolio_id: Uuid,
+
+    #[column(max_width = 3, is_default)]
+    pub code: String,
+
+    #[column(max_width = 25)]
+    pub title: String,
+
+    #[column(max_width = 10, is_default)]
+    pub date: NaiveDate,
+
+    #[column(max_width = 30, is_default)]
+    pub price: f64,
+
+    #[column(max_width = 15)]
+    pub normalized_weight: f32,
+
+    #[column(header = "Owned", max_width = 10, is_default)]
+    pub owned_amount: u32,
+
+    #[column(max_width = 30, is_default)]
+    pub money_spent: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub value: f64,
+}
+
+impl_table!(
+    PortfolioHolding,
+    PortfolioHoldingColumn,
+    PortfolioHoldingRow
+);
+
+impl PortfolioHolding {
+    pub fn new(portfolio: &Portfolio, price: PortfolioFundPrice) -> Self {
+        Self {
+            portfolio: portfolio.name.clone(),
+            portfolio_id: portfolio.id,
+            value: price.price * price.owned_amount as f64,
+            code: price.code,
+            title: price.title,
+            date: price.date,
+            price: price.price,
+            normalized_weight: price.normalized_weight,
+            owned_amount: price.owned_amount,
+            money_spent: price.money_spent,
+        }
+    }
+}
@@ -1,7 +1,17 @@
+mod diff;
+mod drift;
+mod holding;
 mod prediction;
-mod price;
-mod update;
+mod purchase;
 
-pub use prediction::{PortfolioFundPrediction, PortfolioFundPredictionColumn};
-pub use price::{PortfolioFundPrice, PortfolioFundPriceColumn};
-pub use update::PortfolioFundUpdate;
+pub use diff::{PortfolioFundDiff, PortfolioFundDiffColumn};
+pub use drift::{PortfolioFundDrift, PortfolioFundDriftColumn};
+pub use holding::{PortfolioHolding, PortfolioHoldingColumn};
+pub use pfo_client::{
+    PortfolioFundPrediction, PortfolioFundPrice, PortfolioFundPriceColumn, PortfolioFundUpdate,
+};
+pub use prediction::{
+    PortfolioFundPredictionAllocation, PortfolioFundPredictionAllocationColumn,
+    PortfolioFundPredictionComparison, predictions_cost, unit_price,
+};
+pub use purchase::PortfolioFundPurchase;
@@ -1,10 +1,40 @@
 use clap::ValueEnum;
 use pfo_core::impl_table;
 use pfo_derive::OutputTable;
-use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, OutputTable)]
-pub struct PortfolioFundPrediction {
+use crate::portfolio::{PortfolioFundPrediction, PortfolioFundPrice};
+
+/// Amounts predicted by the server next to amounts allocated locally for the same budget
+#[derive(Debug, OutputTable)]
+pub struct PortfolioFundPredictionComparison {
+    #[column(max_width = 3, is_default)]
+    pub code: String,
+
+    #[column(max_width = 25)]
+    pub title: String,
+
+    #[column(max_width = 15, is_default)]
+    pub price: f32,
+
+    #[column(header = "Server", max_width = 10, is_default, left_align = false)]
+    pub server_amount: u32,
+
+    #[column(header = "Local", max_width = 10, is_default, left_align = false)]
+    pub local_amount: u32,
+
+    #[column(max_width = 10, is_default, left_align = false)]
+    pub difference: i64,
+}
+
+impl_table!(
+    PortfolioFundPredictionComparison,
+    PortfolioFundPredictionComparisonColumn,
+    PortfolioFundPredictionComparisonRow
+);
+
+/// A predicted purchase with its cost and how it moves the fund towards its target weight
+#[derive(Debug, OutputTable)]
+pub struct PortfolioFundPredictionAllocation {
     #[column(max_width = 3, is_default)]
     pub code: String,
 
@@ -17,12 +47,165 @@ pub struct PortfolioFundPrediction {
     #[column(max_width = 10, is_default)]
     pub amount: u32,
 
-    #[column(max_width = 15, is_default)]
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub cost: f64,
+
+    #[column(header = "Target", max_width = 15, is_default)]
     pub weight: f32,
+
+    #[column(max_width = 30, left_align = false)]
+    pub weight_before: Option<f64>,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub weight_after: Option<f64>,
+
+    #[column(max_width = 30, left_align = false)]
+    pub drift_before: Option<f64>,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub drift_after: Option<f64>,
 }
 
 impl_table!(
-    PortfolioFundPrediction,
-    PortfolioFundPredictionColumn,
-    PortfolioFundPredictionRow
+    PortfolioFundPredictionAllocation,
+    PortfolioFundPredictionAllocationColumn,
+    PortfolioFundPredictionAllocationRow
 );
+
+impl PortfolioFundPredictionAllocation {
+    /// Compute costs and weights of funds before and after buying predicted amounts.
+    ///
+    /// Drift is the difference between the share of a fund in portfolio value and its normalized
+    /// weight, positive when the fund is overweight.
+    pub fn from_predictions(
+        predictions: &[PortfolioFundPrediction],
+        prices: &[PortfolioFundPrice],
+    ) -> Vec<Self> {
+        let price_of = |code: &str| prices.iter().find(|p| p.code == code);
+
+        // Holdings left out of predictions still count towards portfolio value
+        let total_before: f64 = prices.iter().map(|p| p.owned_amount as f64 * p.price).sum();
+        let total_after = total_before + predictions_cost(predictions, prices);
+        let values = predictions.iter().map(|prediction| {
+            let owned = price_of(&prediction.code).map_or(0, |p| p.owned_amount);
+            let price = unit_price(prediction, prices);
+            let before = owned as f64 * price;
+            (before, before + prediction.amount as f64 * price)
+        });
+
+        let share = |value: f64, total: f64| (total > 0.0).then(|| value / total);
+
+        predictions
+            .iter()
+            .zip(values)
+            .map(|(prediction, (before, after))| {
+                let target =
+                    price_of(&prediction.code).map_or(prediction.weight, |p| p.normalized_weight);
+                let weight_before = share(before, total_before);
+                let weight_after = share(after, total_after);
+
+                Self {
+                    code: prediction.code.clone(),
+                    title: prediction.title.clone(),
+                    price: prediction.price,
+                    amount: prediction.amount,
+                    cost: prediction.amount as f64 * unit_price(prediction, prices),
+                    weight: target,
+                    weight_before,
+                    weight_after,
+                    drift_before: weight_before.map(|w| w - target as f64),
+                    drift_after: weight_after.map(|w| w - target as f64),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Price of a predicted fund in `prices`. The predicted price is rounded to f32 and only used for
+/// funds missing from `prices`.
+pub fn unit_price(prediction: &PortfolioFundPrediction, prices: &[PortfolioFundPrice]) -> f64 {
+    prices
+        .iter()
+        .find(|p| p.code == prediction.code)
+        .map_or(prediction.price as f64, |p| p.price)
+}
+
+/// Cost of buying predicted amounts at prices of the portfolio funds
+pub fn predictions_cost(
+    predictions: &[PortfolioFundPrediction],
+    prices: &[PortfolioFundPrice],
+) -> f64 {
+    predictions
+        .iter()
+        .map(|p| p.amount as f64 * unit_price(p, prices))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn price(
+        code: &str,
+        price: f64,
+        normalized_weight: f32,
+        owned_amount: u32,
+    ) -> PortfolioFundPrice {
+        PortfolioFundPrice {
+            portfolio_id: Uuid::nil(),
+            code: code.to_string(),
+            title: code.to_string(),
+            date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            price,
+            normalized_weight,
+            min_amount: 0,
+            owned_amount,
+            money_spent: 0.0,
+        }
+    }
+
+    fn prediction(code: &str, price: f32, weight: f32, amount: u32) -> PortfolioFundPrediction {
+        PortfolioFundPrediction {
+            code: code.to_string(),
+            title: code.to_string(),
+            price,
+            amount,
+            weight,
+        }
+    }
+
+    #[test]
+    fn weights_count_holdings_without_predictions() {
+        let prices = [price("AAA", 10.0, 0.5, 5), price("BBB", 5.0, 0.5, 10)];
+        let predictions = [prediction("AAA", 10.0, 0.5, 10)];
+
+        let allocations =
+            PortfolioFundPredictionAllocation::from_predictions(&predictions, &prices);
+
+        assert_eq!(allocations.len(), 1);
+        assert_eq!(allocations[0].cost, 100.0);
+        assert_eq!(allocations[0].weight_before, Some(0.5));
+        assert_eq!(allocations[0].weight_after, Some(0.75));
+        assert_eq!(allocations[0].drift_after, Some(0.25));
+    }
+
+    #[test]
+    fn predicted_fund_outside_prices_adds_its_cost() {
+        let prices = [price("AAA", 10.0, 1.0, 10)];
+        let predictions = [
+            prediction("AAA", 10.0, 0.5, 0),
+            prediction("CCC", 20.0, 0.5, 5),
+        ];
+
+        let allocations =
+            PortfolioFundPredictionAllocation::from_predictions(&predictions, &prices);
+
+        assert_eq!(allocations[0].weight_after, Some(0.5));
+        assert_eq!(allocations[1].weight_before, Some(0.0));
+        assert_eq!(allocations[1].weight_after, Some(0.5));
+        assert_eq!(allocations[1].drift_after, Some(0.0));
+    }
+}
@@ -0,0 +1,31 @@
+use clap::ValueEnum;
+use pfo_core::impl_table;
+use pfo_derive::OutputTable;
+
+/// Units of a fund to buy and the resulting holding after the purchase
+#[derive(Debug, OutputTable)]
+pub struct PortfolioFundPurchase {
+    #[column(max_width = 3, is_default)]
+    pub code: String,
+
+    #[column(max_width = 10, is_default, left_align = false)]
+    pub amount: u32,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub price: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub cost: f64,
+
+    #[column(header = "Owned", max_width = 10, is_default, left_align = false)]
+    pub owned_amount: u32,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub money_spent: f64,
+}
+
+impl_table!(
+    PortfolioFundPurchase,
+    PortfolioFundPurchaseColumn,
+    PortfolioFundPurchaseRow
+);
@@ -0,0 +1,347 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDateTime;
+use clap::ValueEnum;
+use pfo_core::impl_table;
+use pfo_derive::OutputTable;
+use uuid::Uuid;
+
+use crate::portfolio::{PortfolioFundPrice, PortfolioFundUpdate, PortfolioUpdate};
+
+/// Sum of weights given to funds when their normalized weights are restored, large enough to keep
+/// normalized weights close to the original ones
+const WEIGHT_SCALE: f32 = 10_000.0;
+
+/// An update sent to a portfolio, kept in the local journal so that it can be undone
+#[derive(Debug, OutputTable)]
+pub struct PortfolioJournalEntry {
+    #[column(max_width = 10, is_default)]
+    pub id: u32,
+
+    #[column(max_width = 36)]
+    pub portfolio_id: Uuid,
+
+    #[column(max_width = 19, is_default)]
+    pub time: NaiveDateTime,
+
+    #[column(max_width = 30, is_default)]
+    pub added: String,
+
+    #[column(max_width = 30, is_default)]
+    pub updated: String,
+
+    #[column(max_width = 30, is_default)]
+    pub removed: String,
+
+    #[column(max_width = 6, is_default)]
+    pub undone: bool,
+}
+
+impl_table!(
+    PortfolioJournalEntry,
+    PortfolioJournalEntryColumn,
+    PortfolioJournalEntryRow
+);
+
+/// An update together with the funds of the portfolio right before it was sent.
+///
+/// All funds are kept, not only the affected ones, since normalized weights of a portfolio depend on
+/// every fund in it.
+pub struct PortfolioJournalRecord {
+    pub update: PortfolioUpdate,
+    pub funds: Vec<PortfolioFundPrice>,
+}
+
+impl PortfolioJournalRecord {
+    fn find(&self, code: &str) -> Option<&PortfolioFundPrice> {
+        self.funds.iter().find(|f| f.code == code)
+    }
+
+    /// Codes of funds added, updated and removed by `update` sent when the portfolio had `funds`,
+    /// comma separated
+    pub fn changes_of(
+        update: &PortfolioUpdate,
+        funds: &[PortfolioFundPrice],
+    ) -> (String, String, String) {
+        let contains = |code: &str| funds.iter().any(|f| f.code == code);
+
+        let mut added = Vec::new();
+        let mut updated = Vec::new();
+        for fund in &update.add_codes {
+            if contains(&fund.fund_code) {
+                updated.push(fund.fund_code.as_str());
+            } else {
+                added.push(fund.fund_code.as_str());
+            }
+        }
+        let mut removed: Vec<&str> = update
+            .remove_codes
+            .iter()
+            .filter(|c| contains(c))
+            .map(String::as_str)
+            .collect();
+
+        added.sort();
+        updated.sort();
+        removed.sort();
+        (added.join(","), updated.join(","), removed.join(","))
+    }
+
+    /// Update that brings funds affected by the update back to their saved state.
+    ///
+    /// Funds added by the update are removed, updated and removed ones get their saved min amount,
+    /// owned amount and money spent back. Server only reports normalized weights, so when the
+    /// update removed a fund or changed a weight, weights of every fund still in the portfolio
+    /// (`current`) are replaced with saved normalized weights scaled to integers.
+    pub fn inverse(&self, current: &[PortfolioFundPrice]) -> PortfolioUpdate {
+        let restore_weights = self
+            .update
+            .remove_codes
+            .iter()
+            .any(|c| self.find(c).is_some())
+            || self
+                .update
+                .add_codes
+                .iter()
+                .any(|f| f.weight.is_some() && self.find(&f.fund_code).is_some());
+        let scaled_weight = |fund: &PortfolioFundPrice| {
+            restore_weights.then(|| (fund.normalized_weight * WEIGHT_SCALE).round() as u32)
+        };
+
+        let affected: HashSet<&str> = self
+            .update
+            .add_codes
+            .iter()
+            .map(|f| f.fund_code.as_str())
+            .chain(self.update.remove_codes.iter().map(String::as_str))
+            .collect();
+
+        let mut add_codes = HashSet::new();
+        for fund in &self.funds {
+            if affected.contains(fund.code.as_str()) {
+                add_codes.insert(PortfolioFundUpdate {
+                    fund_code: fund.code.clone(),
+                    weight: scaled_weight(fund),
+                    min_amount: Some(fund.min_amount),
+                    owned_amount: Some(fund.owned_amount),
+                    total_money_spent: Some(fund.money_spent),
+                });
+            } else if restore_weights && current.iter().any(|c| c.code == fund.code) {
+                add_codes.insert(PortfolioFundUpdate {
+                    fund_code: fund.code.clone(),
+                    weight: scaled_weight(fund),
+                    min_amount: None,
+                    owned_amount: None,
+                    total_money_spent: None,
+                });
+            }
+        }
+
+        let remove_codes = self
+            .update
+            .add_codes
+            .iter()
+            .filter(|f| self.find(&f.fund_code).is_none())
+            .map(|f| f.fund_code.clone())
+            .collect();
+
+        PortfolioUpdate {
+            add_codes,
+            remove_codes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    /// Fund as kept by the server, with its integer weight
+    #[derive(Clone)]
+    struct Fund {
+        code: String,
+        weight: u32,
+        min_amount: u32,
+        owned_amount: u32,
+        money_spent: f64,
+    }
+
+    fn fund(code: &str, weight: u32, owned_amount: u32, money_spent: f64) -> Fund {
+        Fund {
+            code: code.to_string(),
+            weight,
+            min_amount: 1,
+            owned_amount,
+            money_spent,
+        }
+    }
+
+    /// Funds as reported by the server, sorted by code
+    fn prices(funds: &[Fund]) -> Vec<PortfolioFundPrice> {
+        let total: u32 = funds.iter().map(|f| f.weight).sum();
+        let mut prices: Vec<PortfolioFundPrice> = funds
+            .iter()
+            .map(|f| PortfolioFundPrice {
+                portfolio_id: Uuid::nil(),
+                code: f.code.clone(),
+                title: f.code.clone(),
+                date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                price: 10.0,
+                normalized_weight: f.weight as f32 / total as f32,
+                min_amount: f.min_amount,
+                owned_amount: f.owned_amount,
+                money_spent: f.money_spent,
+            })
+            .collect();
+        prices.sort_by(|a, b| a.code.cmp(&b.code));
+        prices
+    }
+
+    /// Apply `update` like the server does
+    fn apply(funds: &[Fund], update: &PortfolioUpdate) -> Vec<Fund> {
+        let mut funds: Vec<Fund> = funds
+            .iter()
+            .filter(|f| !update.remove_codes.contains(&f.code))
+            .cloned()
+            .collect();
+
+        for add in &update.add_codes {
+            let index = match funds.iter().position(|f| f.code == add.fund_code) {
+                Some(index) => index,
+                None => {
+                    funds.push(Fund {
+                        code: add.fund_code.clone(),
+                        weight: 1,
+                        min_amount: 0,
+                        owned_amount: 0,
+                        money_spent: 0.0,
+                    });
+                    funds.len() - 1
+                }
+            };
+
+            let fund = &mut funds[index];
+            fund.weight = add.weight.unwrap_or(fund.weight);
+            fund.min_amount = add.min_amount.unwrap_or(fund.min_amount);
+            fund.owned_amount = add.owned_amount.unwrap_or(fund.owned_amount);
+            fund.money_spent = add.total_money_spent.unwrap_or(fund.money_spent);
+        }
+
+        funds
+    }
+
+    fn fund_update(
+        code: &str,
+        weight: Option<u32>,
+        owned_amount: Option<u32>,
+    ) -> PortfolioFundUpdate {
+        PortfolioFundUpdate {
+            fund_code: code.to_string(),
+            weight,
+            min_amount: None,
+            owned_amount,
+            total_money_spent: owned_amount.map(|o| o as f64 * 10.0),
+        }
+    }
+
+    /// Assert that applying `update` to `funds` and then its inverse restores reported funds
+    fn assert_round_trip(funds: &[Fund], update: PortfolioUpdate) {
+        let before = prices(funds);
+        let updated = apply(funds, &update);
+        let record = PortfolioJournalRecord {
+            update,
+            funds: before.clone(),
+        };
+
+        let inverse = record.inverse(&prices(&updated));
+        let after = prices(&apply(&updated, &inverse));
+
+        assert_eq!(
+            after.iter().map(|f| &f.code).collect::<Vec<_>>(),
+            before.iter().map(|f| &f.code).collect::<Vec<_>>()
+        );
+        for (after, before) in after.iter().zip(&before) {
+            assert!(
+                (after.normalized_weight - before.normalized_weight).abs() < 1e-4,
+                "weight of {}: {} != {}",
+                after.code,
+                after.normalized_weight,
+                before.normalized_weight
+            );
+            assert_eq!(after.min_amount, before.min_amount);
+            assert_eq!(after.owned_amount, before.owned_amount);
+            assert_eq!(after.money_spent, before.money_spent);
+        }
+    }
+
+    fn portfolio() -> Vec<Fund> {
+        vec![
+            fund("AAA", 2, 10, 90.0),
+            fund("BBB", 1, 20, 104.0),
+            fund("CCC", 3, 0, 0.0),
+        ]
+    }
+
+    #[test]
+    fn inverse_removes_added_funds() {
+        let update = || PortfolioUpdate {
+            add_codes: HashSet::from([fund_update("DDD", Some(4), Some(3))]),
+            remove_codes: HashSet::new(),
+        };
+
+        let record = PortfolioJournalRecord {
+            update: update(),
+            funds: prices(&portfolio()),
+        };
+        let inverse = record.inverse(&prices(&apply(&portfolio(), &update())));
+        assert!(inverse.add_codes.is_empty());
+        assert_eq!(inverse.remove_codes, HashSet::from(["DDD".to_string()]));
+
+        assert_round_trip(&portfolio(), update());
+    }
+
+    #[test]
+    fn inverse_restores_removed_funds() {
+        assert_round_trip(
+            &portfolio(),
+            PortfolioUpdate {
+                add_codes: HashSet::new(),
+                remove_codes: HashSet::from(["BBB".to_string()]),
+            },
+        );
+    }
+
+    #[test]
+    fn inverse_restores_changed_funds() {
+        assert_round_trip(
+            &portfolio(),
+            PortfolioUpdate {
+                add_codes: HashSet::from([fund_update("AAA", Some(7), Some(12))]),
+                remove_codes: HashSet::new(),
+            },
+        );
+        assert_round_trip(
+            &portfolio(),
+            PortfolioUpdate {
+                add_codes: HashSet::from([fund_update("BBB", None, Some(25))]),
+                remove_codes: HashSet::new(),
+            },
+        );
+    }
+
+    #[test]
+    fn inverse_restores_mixed_updates() {
+        assert_round_trip(
+            &portfolio(),
+            PortfolioUpdate {
+                add_codes: HashSet::from([
+                    fund_update("AAA", Some(1), None),
+                    fund_update("DDD", Some(2), Some(1)),
+                ]),
+                remove_codes: HashSet::from(["CCC".to_string(), "ZZZ".to_string()]),
+            },
+        );
+    }
+}
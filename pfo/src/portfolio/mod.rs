@@ -1,25 +1,25 @@
 mod fund;
-mod update;
+mod journal;
+mod overview;
+mod performance;
+mod snapshot;
+mod transaction;
+
+pub use pfo_client::{Portfolio, PortfolioColumn, PortfolioUpdate};
 
 pub use fund::{
-    PortfolioFundPrediction, PortfolioFundPredictionColumn, PortfolioFundPrice,
-    PortfolioFundPriceColumn, PortfolioFundUpdate,
+    PortfolioFundDiff, PortfolioFundDiffColumn, PortfolioFundDrift, PortfolioFundDriftColumn,
+    PortfolioFundPrediction, PortfolioFundPredictionAllocation,
+    PortfolioFundPredictionAllocationColumn, PortfolioFundPredictionComparison, PortfolioFundPrice,
+    PortfolioFundPriceColumn, PortfolioFundPurchase, PortfolioFundUpdate, PortfolioHolding,
+    PortfolioHoldingColumn, predictions_cost, unit_price,
+};
+pub use journal::{PortfolioJournalEntry, PortfolioJournalEntryColumn, PortfolioJournalRecord};
+pub use overview::{PortfolioFundExposure, PortfolioFundExposureColumn, PortfolioSummary};
+pub use performance::{
+    PortfolioFundPerformance, PortfolioFundPerformanceColumn, PortfolioPerformance, Valuations,
+};
+pub use snapshot::{PortfolioSnapshot, PortfolioSnapshotColumn};
+pub use transaction::{
+    PortfolioTransaction, PortfolioTransactionColumn, TransactionKind, TransactionRecord,
 };
-pub use update::PortfolioUpdate;
-
-use clap::ValueEnum;
-use pfo_core::impl_table;
-use pfo_derive::OutputTable;
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
-
-#[derive(Debug, Deserialize, Serialize, OutputTable)]
-pub struct Portfolio {
-    #[column(max_width = 36, is_default)]
-    pub id: Uuid,
-
-    #[column(max_width = 50, is_default)]
-    pub name: String,
-}
-
-impl_table!(Portfolio, PortfolioColumn, PortfolioRow);
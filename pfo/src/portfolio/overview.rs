@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use pfo_core::impl_table;
+use pfo_derive::OutputTable;
+
+use crate::portfolio::{Portfolio, PortfolioHolding};
+
+fn share(value: f64, total: f64) -> Option<f64> {
+    (total > 0.0).then(|| value / total)
+}
+
+/// Holdings of a fund summed over all portfolios
+#[derive(Debug, OutputTable)]
+pub struct PortfolioFundExposure {
+    #[column(max_width = 3, is_default)]
+    pub code: String,
+
+    #[column(max_width = 25)]
+    pub title: String,
+
+    #[column(max_width = 10)]
+    pub date: NaiveDate,
+
+    #[column(max_width = 30, left_align = false)]
+    pub price: f64,
+
+    #[column(max_width = 10, is_default, left_align = false)]
+    pub units: u32,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub money_spent: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub value: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub share: Option<f64>,
+
+    #[column(header = "Units By Portfolio", max_width = 50, is_default)]
+    pub portfolios: String,
+}
+
+impl_table!(
+    PortfolioFundExposure,
+    PortfolioFundExposureColumn,
+    PortfolioFundExposureRow
+);
+
+impl PortfolioFundExposure {
+    /// Merge `holdings` of the same fund, sorted by code. Price is the latest one of the fund.
+    pub fn merge(holdings: &[PortfolioHolding]) -> Vec<Self> {
+        let mut by_code: BTreeMap<&str, Vec<&PortfolioHolding>> = BTreeMap::new();
+        for holding in holdings {
+            by_code.entry(&holding.code).or_default().push(holding);
+        }
+
+        let total: f64 = holdings.iter().map(|h| h.value).sum();
+        by_code
+            .into_iter()
+            .map(|(code, holdings)| {
+                let latest = holdings
+                    .iter()
+                    .max_by_key(|h| h.date)
+                    .expect("fund has at least one holding");
+                let value = holdings.iter().map(|h| h.value).sum();
+
+                Self {
+                    code: code.to_string(),
+                    title: latest.title.clone(),
+                    date: latest.date,
+                    price: latest.price,
+                    units: holdings.iter().map(|h| h.owned_amount).sum(),
+                    money_spent: holdings.iter().map(|h| h.money_spent).sum(),
+                    value,
+                    share: share(value, total),
+                    portfolios: holdings
+                        .iter()
+                        .map(|h| format!("{} {}", h.portfolio, h.owned_amount))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Totals of the holdings of one portfolio
+#[derive(Debug, OutputTable)]
+pub struct PortfolioSummary {
+    #[column(max_width = 50, is_default)]
+    pub name: String,
+
+    #[column(max_width = 10, is_default, left_align = false)]
+    pub funds: u32,
+
+    #[column(max_width = 10, is_default, left_align = false)]
+    pub units: u32,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub money_spent: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub value: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub profit: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub share: Option<f64>,
+}
+
+impl_table!(
+    PortfolioSummary,
+    PortfolioSummaryColumn,
+    PortfolioSummaryRow
+);
+
+impl PortfolioSummary {
+    /// Summary of each of `portfolios`, including those without any of `holdings`
+    pub fn summarize(portfolios: &[Portfolio], holdings: &[PortfolioHolding]) -> Vec<Self> {
+        let total: f64 = holdings.iter().map(|h| h.value).sum();
+
+        portfolios
+            .iter()
+            .map(|portfolio| {
+                let holdings: Vec<&PortfolioHolding> = holdings
+                    .iter()
+                    .filter(|h| h.portfolio_id == portfolio.id)
+                    .collect();
+                let money_spent = holdings.iter().map(|h| h.money_spent).sum();
+                let value = holdings.iter().map(|h| h.value).sum();
+
+                Self {
+                    name: portfolio.name.clone(),
+                    funds: holdings.len() as u32,
+                    units: holdings.iter().map(|h| h.owned_amount).sum(),
+                    money_spent,
+                    value,
+                    profit: value - money_spent,
+                    share: share(value, total),
+                }
+            })
+            .collect()
+    }
+}
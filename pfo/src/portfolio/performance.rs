@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use pfo_core::impl_table;
+use pfo_derive::OutputTable;
+
+use crate::analytics::{CashFlow, Trade, time_weighted_return, xirr};
+use crate::portfolio::PortfolioTransaction;
+
+/// Price and its date used to value the current holding of each fund
+pub type Valuations<'a> = HashMap<&'a str, (NaiveDate, f64)>;
+
+struct Summary {
+    units: i64,
+    invested: f64,
+    proceeds: f64,
+    value: Option<f64>,
+    xirr: Option<f64>,
+    twr: Option<f64>,
+}
+
+impl Summary {
+    fn new<F>(transactions: &[&PortfolioTransaction], valuations: &Valuations, price_at: F) -> Self
+    where
+        F: Fn(&str, NaiveDate) -> Option<f64>,
+    {
+        let mut flows: Vec<CashFlow> = transactions.iter().map(|t| t.cash_flow()).collect();
+        let trades: Vec<Trade> = transactions.iter().map(|t| t.trade()).collect();
+
+        let invested = -flows.iter().map(|f| f.amount.min(0.0)).sum::<f64>();
+        let proceeds = flows.iter().map(|f| f.amount.max(0.0)).sum::<f64>();
+
+        let mut units: HashMap<&str, i64> = HashMap::new();
+        for trade in &trades {
+            *units.entry(trade.code).or_default() += trade.units;
+        }
+
+        let mut value = Some(0.0);
+        for (code, units) in units.iter().filter(|(_, units)| **units > 0) {
+            match valuations.get(code) {
+                Some((date, price)) => {
+                    let amount = *units as f64 * price;
+                    flows.push(CashFlow {
+                        date: *date,
+                        amount,
+                    });
+                    value = value.map(|v| v + amount);
+                }
+                None => {
+                    log::warn!("No price found to value holding of {}", code);
+                    value = None;
+                }
+            }
+        }
+
+        // Each fund is valued at its own valuation date, also when the holding period ends later
+        let end = units
+            .keys()
+            .filter_map(|code| valuations.get(code))
+            .map(|(date, _)| *date)
+            .max();
+        let valued_at = |code: &str, date: NaiveDate| match valuations.get(code) {
+            Some((valued, price)) if date >= *valued => Some(*price),
+            _ => price_at(code, date),
+        };
+
+        Self {
+            units: units.values().sum(),
+            invested,
+            proceeds,
+            value,
+            xirr: value.and_then(|_| xirr(&flows)),
+            twr: end.and_then(|end| time_weighted_return(&trades, end, valued_at)),
+        }
+    }
+
+    fn profit(&self) -> Option<f64> {
+        self.value.map(|v| v + self.proceeds - self.invested)
+    }
+}
+
+/// Money-weighted and time-weighted returns of a fund computed from the local ledger
+#[derive(Debug, OutputTable)]
+pub struct PortfolioFundPerformance {
+    #[column(max_width = 3, is_default)]
+    pub code: String,
+
+    #[column(max_width = 10, is_default, left_align = false)]
+    pub units: u32,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub invested: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub proceeds: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub value: Option<f64>,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub profit: Option<f64>,
+
+    #[column(header = "XIRR", max_width = 30, is_default, left_align = false)]
+    pub xirr: Option<f64>,
+
+    #[column(header = "TWR", max_width = 30, is_default, left_align = false)]
+    pub twr: Option<f64>,
+}
+
+impl_table!(
+    PortfolioFundPerformance,
+    PortfolioFundPerformanceColumn,
+    PortfolioFundPerformanceRow
+);
+
+impl PortfolioFundPerformance {
+    pub fn new<F>(
+        code: String,
+        transactions: &[&PortfolioTransaction],
+        valuations: &Valuations,
+        price_at: F,
+    ) -> Self
+    where
+        F: Fn(&str, NaiveDate) -> Option<f64>,
+    {
+        let summary = Summary::new(transactions, valuations, price_at);
+
+        Self {
+            code,
+            units: summary.units.max(0) as u32,
+            invested: summary.invested,
+            proceeds: summary.proceeds,
+            value: summary.value,
+            profit: summary.profit(),
+            xirr: summary.xirr,
+            twr: summary.twr,
+        }
+    }
+}
+
+/// Money-weighted and time-weighted returns of a whole portfolio computed from the local ledger
+#[derive(Debug, OutputTable)]
+pub struct PortfolioPerformance {
+    #[column(max_width = 50, is_default)]
+    pub name: String,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub invested: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub proceeds: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub value: Option<f64>,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub profit: Option<f64>,
+
+    #[column(header = "XIRR", max_width = 30, is_default, left_align = false)]
+    pub xirr: Option<f64>,
+
+    #[column(header = "TWR", max_width = 30, is_default, left_align = false)]
+    pub twr: Option<f64>,
+}
+
+impl_table!(
+    PortfolioPerformance,
+    PortfolioPerformanceColumn,
+    PortfolioPerformanceRow
+);
+
+impl PortfolioPerformance {
+    pub fn new<F>(
+        name: String,
+        transactions: &[&PortfolioTransaction],
+        valuations: &Valuations,
+        price_at: F,
+    ) -> Self
+    where
+        F: Fn(&str, NaiveDate) -> Option<f64>,
+    {
+        let summary = Summary::new(transactions, valuations, price_at);
+
+        Self {
+            name,
+            invested: summary.invested,
+            proceeds: summary.proceeds,
+            value: summary.value,
+            profit: summary.profit(),
+            xirr: summary.xirr,
+            twr: summary.twr,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Datelike;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::portfolio::TransactionKind;
+
+    fn date(m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, m, d).unwrap()
+    }
+
+    fn buy(code: &str, date: NaiveDate, units: u32, unit_price: f64) -> PortfolioTransaction {
+        PortfolioTransaction {
+            id: 0,
+            portfolio_id: Uuid::nil(),
+            code: code.to_string(),
+            date,
+            kind: TransactionKind::Buy,
+            units,
+            unit_price,
+            fees: 0.0,
+        }
+    }
+
+    #[test]
+    fn funds_are_valued_at_their_own_date() {
+        let transactions = [
+            buy("AAA", date(1, 1), 10, 10.0),
+            buy("BBB", date(1, 1), 10, 5.0),
+        ];
+        let valuations: Valuations =
+            HashMap::from([("AAA", (date(2, 1), 11.0)), ("BBB", (date(3, 1), 6.0))]);
+        // Later prices of AAA must not be used once it is valued
+        let price_at = |code: &str, date: NaiveDate| match (code, date.month()) {
+            (_, 1) => Some(if code == "AAA" { 10.0 } else { 5.0 }),
+            ("AAA", _) => Some(20.0),
+            _ => None,
+        };
+
+        let fund = PortfolioFundPerformance::new(
+            "AAA".to_string(),
+            &transactions[..1].iter().collect::<Vec<_>>(),
+            &valuations,
+            price_at,
+        );
+        assert_eq!(fund.value, Some(110.0));
+        assert!((fund.twr.unwrap() - 0.1).abs() < 1e-9);
+
+        let total = PortfolioPerformance::new(
+            "Main".to_string(),
+            &transactions.iter().collect::<Vec<_>>(),
+            &valuations,
+            price_at,
+        );
+        assert_eq!(total.value, Some(170.0));
+        assert!((total.twr.unwrap() - 170.0 / 150.0 + 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::NaiveDateTime;
+use clap::ValueEnum;
+use pfo_core::impl_table;
+use pfo_derive::OutputTable;
+use uuid::Uuid;
+
+/// Fund prices of a portfolio saved locally at some point in time
+#[derive(Debug, OutputTable)]
+pub struct PortfolioSnapshot {
+    #[column(max_width = 10, is_default)]
+    pub id: u32,
+
+    #[column(max_width = 36)]
+    pub portfolio_id: Uuid,
+
+    #[column(max_width = 19, is_default)]
+    pub time: NaiveDateTime,
+
+    #[column(max_width = 10, is_default, left_align = false)]
+    pub funds: u32,
+}
+
+impl_table!(
+    PortfolioSnapshot,
+    PortfolioSnapshotColumn,
+    PortfolioSnapshotRow
+);
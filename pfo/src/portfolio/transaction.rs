@@ -0,0 +1,134 @@
+use std::fmt::Display;
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use pfo_core::impl_table;
+use pfo_core::output::ToRowValue;
+use pfo_core::parse_naive_date;
+use pfo_derive::OutputTable;
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+use crate::analytics::{CashFlow, Trade};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionKind {
+    #[default]
+    Buy,
+    Sell,
+}
+
+impl Display for TransactionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionKind::Buy => write!(f, "buy"),
+            TransactionKind::Sell => write!(f, "sell"),
+        }
+    }
+}
+
+impl ToRowValue for TransactionKind {
+    fn to_row_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+fn deserialize_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse_naive_date(&s).map_err(serde::de::Error::custom)
+}
+
+/// Treats empty CSV fields as missing ones
+fn default_if_empty<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Option::<T>::deserialize(deserializer).map(Option::unwrap_or_default)
+}
+
+/// A transaction to be recorded in the ledger, as given on command line or in an imported CSV
+#[derive(Debug, Deserialize)]
+pub struct TransactionRecord {
+    pub code: String,
+
+    #[serde(deserialize_with = "deserialize_date")]
+    pub date: NaiveDate,
+
+    #[serde(default, deserialize_with = "default_if_empty")]
+    pub kind: TransactionKind,
+
+    pub units: u32,
+
+    pub unit_price: f64,
+
+    #[serde(default, deserialize_with = "default_if_empty")]
+    pub fees: f64,
+}
+
+/// A dated buy or sell of a fund in a portfolio, kept in the local ledger
+#[derive(Debug, OutputTable)]
+pub struct PortfolioTransaction {
+    #[column(max_width = 10, is_default)]
+    pub id: u32,
+
+    #[column(max_width = 36)]
+    pub portfolio_id: Uuid,
+
+    #[column(max_width = 3, is_default)]
+    pub code: String,
+
+    #[column(max_width = 10, is_default)]
+    pub date: NaiveDate,
+
+    #[column(max_width = 4, is_default)]
+    pub kind: TransactionKind,
+
+    #[column(max_width = 10, is_default, left_align = false)]
+    pub units: u32,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub unit_price: f64,
+
+    #[column(max_width = 30, is_default, left_align = false)]
+    pub fees: f64,
+}
+
+impl_table!(
+    PortfolioTransaction,
+    PortfolioTransactionColumn,
+    PortfolioTransactionRow
+);
+
+impl PortfolioTransaction {
+    /// Units added to the holding, negative for sells
+    pub fn signed_units(&self) -> i64 {
+        match self.kind {
+            TransactionKind::Buy => self.units.into(),
+            TransactionKind::Sell => -i64::from(self.units),
+        }
+    }
+
+    /// Money paid for a buy or received from a sell, fees included
+    pub fn cash_flow(&self) -> CashFlow {
+        let gross = self.units as f64 * self.unit_price;
+        let amount = match self.kind {
+            TransactionKind::Buy => -(gross + self.fees),
+            TransactionKind::Sell => gross - self.fees,
+        };
+
+        CashFlow {
+            date: self.date,
+            amount,
+        }
+    }
+
+    pub fn trade(&self) -> Trade<'_> {
+        Trade {
+            date: self.date,
+            code: &self.code,
+            units: self.signed_units(),
+        }
+    }
+}
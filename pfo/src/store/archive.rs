@@ -0,0 +1,230 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use pfo_core::sort::SortArguments;
+use rusqlite::{Row, ToSql, params};
+
+use crate::fund::{FundInfo, FundInfoColumn, FundPriceStats, FundPriceStatsColumn};
+use crate::store::Store;
+
+fn fund_info_column(col: &FundInfoColumn) -> &'static str {
+    match col {
+        FundInfoColumn::Code => "code",
+        FundInfoColumn::Title => "title",
+        FundInfoColumn::Provider => "provider",
+        FundInfoColumn::Date => "date",
+        FundInfoColumn::Price => "price",
+        FundInfoColumn::TotalValue => "total_value",
+    }
+}
+
+fn fund_price_stats_column(col: &FundPriceStatsColumn) -> &'static str {
+    match col {
+        FundPriceStatsColumn::Code => "code",
+        FundPriceStatsColumn::Date => "date",
+        FundPriceStatsColumn::Price => "price",
+        FundPriceStatsColumn::TotalValue => "total_value",
+        FundPriceStatsColumn::DailyReturn => "daily_return",
+        FundPriceStatsColumn::MonthlyReturn => "monthly_return",
+        FundPriceStatsColumn::ThreeMonthlyReturn => "three_monthly_return",
+        FundPriceStatsColumn::SixMonthlyReturn => "six_monthly_return",
+        FundPriceStatsColumn::YearlyReturn => "yearly_return",
+        FundPriceStatsColumn::ThreeYearlyReturn => "three_yearly_return",
+        FundPriceStatsColumn::FiveYearlyReturn => "five_yearly_return",
+    }
+}
+
+/// Builds the `WHERE` and `ORDER BY` part of an archive query.
+///
+/// Without a date only the latest archived row of each fund is selected.
+fn build_filter(
+    table: &str,
+    codes: &[String],
+    date: Option<NaiveDate>,
+    order_by: Option<String>,
+) -> (String, Vec<Box<dyn ToSql>>) {
+    let mut conditions = Vec::with_capacity(2);
+    let mut params: Vec<Box<dyn ToSql>> = Vec::with_capacity(codes.len() + 1);
+
+    match date {
+        Some(date) => {
+            conditions.push("date = ?".to_string());
+            params.push(Box::new(date));
+        }
+        None => conditions.push(format!(
+            "(code, date) IN (SELECT code, MAX(date) FROM {} GROUP BY code)",
+            table
+        )),
+    }
+
+    if !codes.is_empty() {
+        conditions.push(format!("code IN ({})", vec!["?"; codes.len()].join(", ")));
+        params.extend(codes.iter().map(|c| Box::new(c.clone()) as Box<dyn ToSql>));
+    }
+
+    let sql = format!(
+        "WHERE {} ORDER BY {}",
+        conditions.join(" AND "),
+        order_by.unwrap_or("code ASC".into())
+    );
+
+    (sql, params)
+}
+
+fn fund_info_from_row(row: &Row) -> rusqlite::Result<FundInfo> {
+    Ok(FundInfo {
+        code: row.get("code")?,
+        title: row.get("title")?,
+        provider: row.get("provider")?,
+        date: row.get("date")?,
+        price: row.get("price")?,
+        total_value: row.get("total_value")?,
+    })
+}
+
+fn fund_price_stats_from_row(row: &Row) -> rusqlite::Result<FundPriceStats> {
+    Ok(FundPriceStats {
+        code: row.get("code")?,
+        date: row.get("date")?,
+        price: row.get("price")?,
+        total_value: row.get("total_value")?,
+        daily_return: row.get("daily_return")?,
+        monthly_return: row.get("monthly_return")?,
+        three_monthly_return: row.get("three_monthly_return")?,
+        six_monthly_return: row.get("six_monthly_return")?,
+        yearly_return: row.get("yearly_return")?,
+        three_yearly_return: row.get("three_yearly_return")?,
+        five_yearly_return: row.get("five_yearly_return")?,
+    })
+}
+
+impl Store {
+    pub fn archive_funds(&mut self, funds: &[FundInfo]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO fund_info (code, date, title, provider, price, total_value)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+
+            for fund in funds {
+                stmt.execute(params![
+                    fund.code,
+                    fund.date,
+                    fund.title,
+                    fund.provider,
+                    fund.price,
+                    fund.total_value,
+                ])
+                .context(format!(
+                    "Failed to archive fund {} at {}",
+                    fund.code, fund.date
+                ))?;
+            }
+        }
+        tx.commit().context("Failed to commit archived funds")?;
+
+        Ok(funds.len())
+    }
+
+    pub fn archive_fund_price_stats(&mut self, stats: &[FundPriceStats]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO fund_price_stats (
+                    code, date, price, total_value, daily_return, monthly_return,
+                    three_monthly_return, six_monthly_return, yearly_return,
+                    three_yearly_return, five_yearly_return
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+
+            for stat in stats {
+                stmt.execute(params![
+                    stat.code,
+                    stat.date,
+                    stat.price,
+                    stat.total_value,
+                    stat.daily_return,
+                    stat.monthly_return,
+                    stat.three_monthly_return,
+                    stat.six_monthly_return,
+                    stat.yearly_return,
+                    stat.three_yearly_return,
+                    stat.five_yearly_return,
+                ])
+                .context(format!(
+                    "Failed to archive fund price stats of {} at {}",
+                    stat.code, stat.date
+                ))?;
+            }
+        }
+        tx.commit()
+            .context("Failed to commit archived fund price stats")?;
+
+        Ok(stats.len())
+    }
+
+    pub fn get_funds(
+        &self,
+        codes: &[String],
+        date: Option<NaiveDate>,
+        sort: Option<SortArguments<FundInfoColumn>>,
+    ) -> Result<Vec<FundInfo>> {
+        let order_by = sort.map(|s| format!("{} {}", fund_info_column(&s.by), s.dir));
+        let (filter, params) = build_filter("fund_info", codes, date, order_by);
+
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT * FROM fund_info {}", filter))?;
+        let funds = stmt
+            .query_map(rusqlite::params_from_iter(params), fund_info_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Error when reading archived fund informations")?;
+
+        Ok(funds)
+    }
+
+    /// Archived prices of fund `code` between optional `from` and `to` dates, inclusive
+    pub fn get_fund_prices(
+        &self,
+        code: &str,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<(NaiveDate, f64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT date, price FROM fund_info
+             WHERE code = ?1 AND (?2 IS NULL OR date >= ?2) AND (?3 IS NULL OR date <= ?3)
+             ORDER BY date",
+        )?;
+        let prices = stmt
+            .query_map(params![code, from, to], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context(format!("Error when reading archived prices of {}", code))?;
+
+        Ok(prices)
+    }
+
+    pub fn get_fund_price_stats(
+        &self,
+        codes: &[String],
+        date: Option<NaiveDate>,
+        sort: Option<SortArguments<FundPriceStatsColumn>>,
+    ) -> Result<Vec<FundPriceStats>> {
+        let order_by = sort.map(|s| format!("{} {}", fund_price_stats_column(&s.by), s.dir));
+        let (filter, params) = build_filter("fund_price_stats", codes, date, order_by);
+
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT * FROM fund_price_stats {}", filter))?;
+        let stats = stmt
+            .query_map(
+                rusqlite::params_from_iter(params),
+                fund_price_stats_from_row,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Error when reading archived fund price stats")?;
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,185 @@
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use rusqlite::{Row, params};
+use uuid::Uuid;
+
+use crate::error::PfoError;
+use crate::portfolio::{
+    PortfolioFundPrice, PortfolioJournalEntry, PortfolioJournalRecord, PortfolioUpdate,
+};
+use crate::store::{Store, fund_price_from_row, parse_uuid};
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<PortfolioJournalEntry> {
+    Ok(PortfolioJournalEntry {
+        id: row.get("id")?,
+        portfolio_id: parse_uuid(row.get("portfolio_id")?, 1)?,
+        time: row.get("time")?,
+        added: row.get("added")?,
+        updated: row.get("updated")?,
+        removed: row.get("removed")?,
+        undone: row.get("undone")?,
+    })
+}
+
+impl Store {
+    /// Save `funds` of portfolio `id` as they are before `update` is sent, returning id of the
+    /// journal entry. `transaction_ids` are ledger transactions recorded for the update, removed
+    /// again when it is undone.
+    pub fn add_journal_entry(
+        &mut self,
+        id: Uuid,
+        update: &PortfolioUpdate,
+        funds: &[PortfolioFundPrice],
+        transaction_ids: &[u32],
+    ) -> Result<u32> {
+        let update_json =
+            serde_json::to_string(update).context("Failed to serialize portfolio update")?;
+
+        let tx = self.conn.transaction()?;
+        let entry_id = {
+            let (added, updated, removed) = PortfolioJournalRecord::changes_of(update, funds);
+            tx.execute(
+                "INSERT INTO journal (portfolio_id, time, portfolio_update, added, updated, removed)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    id.to_string(),
+                    Local::now().naive_local(),
+                    update_json,
+                    added,
+                    updated,
+                    removed,
+                ],
+            )
+            .context("Failed to add journal entry")?;
+            let entry_id = tx.last_insert_rowid() as u32;
+
+            let mut stmt = tx.prepare(
+                "INSERT INTO journal_funds (journal_id, code, title, date, price, normalized_weight, min_amount, owned_amount, money_spent)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for fund in funds {
+                stmt.execute(params![
+                    entry_id,
+                    fund.code,
+                    fund.title,
+                    fund.date,
+                    fund.price,
+                    fund.normalized_weight,
+                    fund.min_amount,
+                    fund.owned_amount,
+                    fund.money_spent,
+                ])
+                .context(format!("Failed to save fund {} in journal", fund.code))?;
+            }
+
+            let mut stmt = tx.prepare(
+                "INSERT INTO journal_transactions (journal_id, transaction_id) VALUES (?1, ?2)",
+            )?;
+            for transaction_id in transaction_ids {
+                stmt.execute(params![entry_id, transaction_id])
+                    .context(format!(
+                        "Failed to save transaction {} in journal",
+                        transaction_id
+                    ))?;
+            }
+
+            entry_id
+        };
+        tx.commit().context("Failed to commit journal entry")?;
+
+        Ok(entry_id)
+    }
+
+    /// Journal entries of portfolio `id`, newest first
+    pub fn get_journal(&self, id: Uuid) -> Result<Vec<PortfolioJournalEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM journal
+             WHERE portfolio_id = ?1
+             ORDER BY id DESC",
+        )?;
+        let entries = stmt
+            .query_map(params![id.to_string()], entry_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Error when reading journal")?;
+
+        Ok(entries)
+    }
+
+    /// Update and saved funds of journal entry `entry_id` of portfolio `id`
+    pub fn get_journal_record(&self, id: Uuid, entry_id: u32) -> Result<PortfolioJournalRecord> {
+        let update_json: String = self
+            .conn
+            .query_row(
+                "SELECT portfolio_update FROM journal WHERE portfolio_id = ?1 AND id = ?2",
+                params![id.to_string(), entry_id],
+                |row| row.get(0),
+            )
+            .context(format!(
+                "No entry {} in journal of portfolio {}",
+                entry_id, id
+            ))?;
+        let update = serde_json::from_str(&update_json)
+            .context(format!("Invalid update in journal entry {}", entry_id))?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM journal_funds
+             WHERE journal_id = ?1
+             ORDER BY code",
+        )?;
+        let funds = stmt
+            .query_map(params![entry_id], |row| fund_price_from_row(id, row))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context(format!("Error when reading journal entry {}", entry_id))?;
+
+        Ok(PortfolioJournalRecord { update, funds })
+    }
+
+    /// Mark journal entry `entry_id` of portfolio `id` undone and remove ledger transactions
+    /// recorded for its update, all or nothing. Returns the number of removed transactions.
+    pub fn undo_journal_entry(&mut self, id: Uuid, entry_id: u32) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let removed = tx
+            .execute(
+                "DELETE FROM transactions
+                 WHERE portfolio_id = ?1
+                   AND id IN (SELECT transaction_id FROM journal_transactions WHERE journal_id = ?2)",
+                params![id.to_string(), entry_id],
+            )
+            .context(format!(
+                "Failed to remove transactions of journal entry {}",
+                entry_id
+            ))?;
+        tx.execute(
+            "UPDATE journal SET undone = 1 WHERE id = ?1",
+            params![entry_id],
+        )
+        .context(format!("Failed to mark journal entry {} undone", entry_id))?;
+        tx.commit().context(format!(
+            "Failed to commit undo of journal entry {}",
+            entry_id
+        ))?;
+
+        Ok(removed)
+    }
+
+    /// Remove journal entry of an update that could not be sent
+    pub fn remove_journal_entry(&mut self, entry_id: u32) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM journal_funds WHERE journal_id = ?1",
+            params![entry_id],
+        )?;
+        tx.execute(
+            "DELETE FROM journal_transactions WHERE journal_id = ?1",
+            params![entry_id],
+        )?;
+        let removed = tx.execute("DELETE FROM journal WHERE id = ?1", params![entry_id])?;
+        if removed == 0 {
+            bail!(PfoError::NotFound(format!("No journal entry {}", entry_id)));
+        }
+        tx.commit()
+            .context(format!("Failed to remove journal entry {}", entry_id))?;
+
+        Ok(())
+    }
+}
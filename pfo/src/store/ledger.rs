@@ -0,0 +1,120 @@
+use anyhow::{Context, Result, bail};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use rusqlite::{Row, ToSql, params};
+use uuid::Uuid;
+
+use crate::error::PfoError;
+use crate::portfolio::{PortfolioTransaction, TransactionKind, TransactionRecord};
+use crate::store::Store;
+
+impl ToSql for TransactionKind {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for TransactionKind {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_str()? {
+            "buy" => Ok(TransactionKind::Buy),
+            "sell" => Ok(TransactionKind::Sell),
+            other => Err(FromSqlError::Other(
+                format!("Invalid transaction kind {}", other).into(),
+            )),
+        }
+    }
+}
+
+fn transaction_from_row(row: &Row) -> rusqlite::Result<PortfolioTransaction> {
+    let portfolio_id: String = row.get("portfolio_id")?;
+
+    Ok(PortfolioTransaction {
+        id: row.get("id")?,
+        portfolio_id: Uuid::parse_str(&portfolio_id).map_err(|err| {
+            rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, err.into())
+        })?,
+        code: row.get("code")?,
+        date: row.get("date")?,
+        kind: row.get("kind")?,
+        units: row.get("units")?,
+        unit_price: row.get("unit_price")?,
+        fees: row.get("fees")?,
+    })
+}
+
+impl Store {
+    /// Record transactions of portfolio `id` in the ledger, all or none of them
+    pub fn add_transactions(
+        &mut self,
+        id: Uuid,
+        records: &[TransactionRecord],
+    ) -> Result<Vec<u32>> {
+        let tx = self.conn.transaction()?;
+        let mut ids = Vec::with_capacity(records.len());
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO transactions (portfolio_id, code, date, kind, units, unit_price, fees)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+
+            for record in records {
+                stmt.execute(params![
+                    id.to_string(),
+                    record.code,
+                    record.date,
+                    record.kind,
+                    record.units,
+                    record.unit_price,
+                    record.fees,
+                ])
+                .context(format!(
+                    "Failed to record {} of {} at {}",
+                    record.kind, record.code, record.date
+                ))?;
+
+                ids.push(tx.last_insert_rowid() as u32);
+            }
+        }
+        tx.commit().context("Failed to commit transactions")?;
+
+        Ok(ids)
+    }
+
+    /// Transactions of portfolio `id`, optionally only of fund `code`, ordered by date
+    pub fn get_transactions(
+        &self,
+        id: Uuid,
+        code: Option<&str>,
+    ) -> Result<Vec<PortfolioTransaction>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM transactions
+             WHERE portfolio_id = ?1 AND (?2 IS NULL OR code = ?2)
+             ORDER BY date, id",
+        )?;
+        let transactions = stmt
+            .query_map(params![id.to_string(), code], transaction_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Error when reading transactions from ledger")?;
+
+        Ok(transactions)
+    }
+
+    pub fn remove_transaction(&mut self, id: Uuid, transaction_id: u32) -> Result<()> {
+        let removed = self
+            .conn
+            .execute(
+                "DELETE FROM transactions WHERE portfolio_id = ?1 AND id = ?2",
+                params![id.to_string(), transaction_id],
+            )
+            .context("Failed to remove transaction from ledger")?;
+
+        if removed == 0 {
+            bail!(PfoError::NotFound(format!(
+                "No transaction {} in ledger of portfolio {}",
+                transaction_id, id
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+mod archive;
+mod journal;
+mod ledger;
+mod snapshot;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, Row};
+use uuid::Uuid;
+
+use crate::portfolio::PortfolioFundPrice;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS fund_info (
+    code TEXT NOT NULL,
+    date TEXT NOT NULL,
+    title TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    price REAL NOT NULL,
+    total_value REAL NOT NULL,
+    PRIMARY KEY (code, date)
+);
+
+CREATE TABLE IF NOT EXISTS fund_price_stats (
+    code TEXT NOT NULL,
+    date TEXT NOT NULL,
+    price REAL NOT NULL,
+    total_value REAL NOT NULL,
+    daily_return REAL,
+    monthly_return REAL,
+    three_monthly_return REAL,
+    six_monthly_return REAL,
+    yearly_return REAL,
+    three_yearly_return REAL,
+    five_yearly_return REAL,
+    PRIMARY KEY (code, date)
+);
+
+CREATE TABLE IF NOT EXISTS transactions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    portfolio_id TEXT NOT NULL,
+    code TEXT NOT NULL,
+    date TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    units INTEGER NOT NULL,
+    unit_price REAL NOT NULL,
+    fees REAL NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS transactions_portfolio ON transactions (portfolio_id, code, date);
+
+CREATE TABLE IF NOT EXISTS journal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    portfolio_id TEXT NOT NULL,
+    time TEXT NOT NULL,
+    portfolio_update TEXT NOT NULL,
+    added TEXT NOT NULL,
+    updated TEXT NOT NULL,
+    removed TEXT NOT NULL,
+    undone INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    portfolio_id TEXT NOT NULL,
+    time TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS snapshot_funds (
+    snapshot_id INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    title TEXT NOT NULL,
+    date TEXT NOT NULL,
+    price REAL NOT NULL,
+    normalized_weight REAL NOT NULL,
+    min_amount INTEGER NOT NULL,
+    owned_amount INTEGER NOT NULL,
+    money_spent REAL NOT NULL,
+    PRIMARY KEY (snapshot_id, code)
+);
+
+CREATE TABLE IF NOT EXISTS journal_funds (
+    journal_id INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    title TEXT NOT NULL,
+    date TEXT NOT NULL,
+    price REAL NOT NULL,
+    normalized_weight REAL NOT NULL,
+    min_amount INTEGER NOT NULL,
+    owned_amount INTEGER NOT NULL,
+    money_spent REAL NOT NULL,
+    PRIMARY KEY (journal_id, code)
+);
+
+CREATE TABLE IF NOT EXISTS journal_transactions (
+    journal_id INTEGER NOT NULL,
+    transaction_id INTEGER NOT NULL,
+    PRIMARY KEY (journal_id, transaction_id)
+);
+";
+
+fn parse_uuid(value: String, index: usize) -> rusqlite::Result<Uuid> {
+    Uuid::parse_str(&value).map_err(|err| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, err.into())
+    })
+}
+
+/// Read a saved fund of portfolio `portfolio_id` from a row of `journal_funds` or `snapshot_funds`
+fn fund_price_from_row(portfolio_id: Uuid, row: &Row) -> rusqlite::Result<PortfolioFundPrice> {
+    Ok(PortfolioFundPrice {
+        portfolio_id,
+        code: row.get("code")?,
+        title: row.get("title")?,
+        date: row.get("date")?,
+        price: row.get("price")?,
+        normalized_weight: row.get("normalized_weight")?,
+        min_amount: row.get("min_amount")?,
+        owned_amount: row.get("owned_amount")?,
+        money_spent: row.get("money_spent")?,
+    })
+}
+
+/// Local SQLite database that keeps data fetched from the server
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn default_path() -> Result<PathBuf> {
+        dirs::data_dir()
+            .map(|dir| dir.join("pfo").join("pfo.db"))
+            .context("Could not determine user data directory, use --database instead")
+    }
+
+    /// Open the database at `path` or at [`Store::default_path`] if no path is given
+    pub fn open(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::open_path(path),
+            None => Self::open_path(&Self::default_path()?),
+        }
+    }
+
+    fn open_path(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(format!(
+                "Failed to create database directory {}",
+                parent.display()
+            ))?;
+        }
+
+        log::debug!("Opening local database {}", path.display());
+
+        let conn = Connection::open(path)
+            .context(format!("Failed to open local database {}", path.display()))?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to initialize local database schema")?;
+
+        Ok(Self { conn })
+    }
+}
@@ -0,0 +1,126 @@
+use anyhow::{Context, Result, bail};
+use chrono::{Local, NaiveDate};
+use rusqlite::{OptionalExtension, params};
+use uuid::Uuid;
+
+use crate::error::PfoError;
+use crate::portfolio::{PortfolioFundPrice, PortfolioSnapshot};
+use crate::store::{Store, fund_price_from_row, parse_uuid};
+
+impl Store {
+    /// Save `funds` of portfolio `id`, returning id of the snapshot
+    pub fn add_snapshot(&mut self, id: Uuid, funds: &[PortfolioFundPrice]) -> Result<u32> {
+        let tx = self.conn.transaction()?;
+        let snapshot_id = {
+            tx.execute(
+                "INSERT INTO snapshots (portfolio_id, time) VALUES (?1, ?2)",
+                params![id.to_string(), Local::now().naive_local()],
+            )
+            .context("Failed to add snapshot")?;
+            let snapshot_id = tx.last_insert_rowid() as u32;
+
+            let mut stmt = tx.prepare(
+                "INSERT INTO snapshot_funds (snapshot_id, code, title, date, price, normalized_weight, min_amount, owned_amount, money_spent)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for fund in funds {
+                stmt.execute(params![
+                    snapshot_id,
+                    fund.code,
+                    fund.title,
+                    fund.date,
+                    fund.price,
+                    fund.normalized_weight,
+                    fund.min_amount,
+                    fund.owned_amount,
+                    fund.money_spent,
+                ])
+                .context(format!("Failed to save fund {} in snapshot", fund.code))?;
+            }
+
+            snapshot_id
+        };
+        tx.commit().context("Failed to commit snapshot")?;
+
+        Ok(snapshot_id)
+    }
+
+    /// Snapshots of portfolio `id`, newest first
+    pub fn get_snapshots(&self, id: Uuid) -> Result<Vec<PortfolioSnapshot>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.id, s.portfolio_id, s.time, COUNT(f.code) AS funds
+             FROM snapshots s LEFT JOIN snapshot_funds f ON f.snapshot_id = s.id
+             WHERE s.portfolio_id = ?1
+             GROUP BY s.id
+             ORDER BY s.id DESC",
+        )?;
+        let snapshots = stmt
+            .query_map(params![id.to_string()], |row| {
+                Ok(PortfolioSnapshot {
+                    id: row.get("id")?,
+                    portfolio_id: parse_uuid(row.get("portfolio_id")?, 1)?,
+                    time: row.get("time")?,
+                    funds: row.get("funds")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Error when reading snapshots")?;
+
+        Ok(snapshots)
+    }
+
+    /// Id of the latest snapshot of portfolio `id` saved on or before `date`
+    pub fn get_snapshot_on(&self, id: Uuid, date: NaiveDate) -> Result<u32> {
+        let snapshot_id = self
+            .conn
+            .query_row(
+                "SELECT id FROM snapshots
+                 WHERE portfolio_id = ?1 AND date(time) <= ?2
+                 ORDER BY time DESC, id DESC
+                 LIMIT 1",
+                params![id.to_string(), date],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("Error when reading snapshots")?;
+
+        match snapshot_id {
+            Some(snapshot_id) => Ok(snapshot_id),
+            None => bail!(PfoError::NotFound(format!(
+                "No snapshot of portfolio {} saved on or before {}, save one with portfolio snapshot",
+                id, date
+            ))),
+        }
+    }
+
+    /// Funds saved in snapshot `snapshot_id` of portfolio `id`
+    pub fn get_snapshot_funds(
+        &self,
+        id: Uuid,
+        snapshot_id: u32,
+    ) -> Result<Vec<PortfolioFundPrice>> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM snapshots WHERE portfolio_id = ?1 AND id = ?2)",
+            params![id.to_string(), snapshot_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            bail!(PfoError::NotFound(format!(
+                "No snapshot {} of portfolio {}",
+                snapshot_id, id
+            )));
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM snapshot_funds
+             WHERE snapshot_id = ?1
+             ORDER BY code",
+        )?;
+        let funds = stmt
+            .query_map(params![snapshot_id], |row| fund_price_from_row(id, row))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context(format!("Error when reading snapshot {}", snapshot_id))?;
+
+        Ok(funds)
+    }
+}
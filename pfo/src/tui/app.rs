@@ -0,0 +1,470 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use pfo_client::PfoClient;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use uuid::Uuid;
+
+use crate::cli::{UpdateOptions, UpdateReview, check_update, send_update};
+use crate::fund::FundPriceStats;
+use crate::portfolio::{
+    Portfolio, PortfolioFundPredictionAllocation, PortfolioFundPrice, PortfolioUpdate,
+};
+use crate::store::Store;
+use crate::tui::form::{FormEvent, FundForm};
+use crate::tui::table::{TablePane, TableView};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pane {
+    Portfolios,
+    Holdings,
+    Stats,
+    Predictions,
+}
+
+impl Pane {
+    const ALL: [Pane; 4] = [
+        Pane::Portfolios,
+        Pane::Holdings,
+        Pane::Stats,
+        Pane::Predictions,
+    ];
+
+    fn next(self) -> Self {
+        let i = Self::ALL
+            .iter()
+            .position(|p| *p == self)
+            .unwrap_or_default();
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    fn previous(self) -> Self {
+        let i = Self::ALL
+            .iter()
+            .position(|p| *p == self)
+            .unwrap_or_default();
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// What keys currently do, besides normal navigation
+pub enum Mode {
+    Normal,
+    Form(Box<FundForm>),
+    Budget(String),
+    ConfirmUpdate(Uuid, PortfolioUpdate, Vec<String>),
+    Preview(String),
+}
+
+/// Work that needs the server, done after the next draw so that the status line is up to date
+pub enum Action {
+    LoadPortfolios,
+    LoadPortfolio(Uuid),
+    Predict(Uuid, f32),
+    Update(Uuid, PortfolioUpdate),
+    Send(Uuid, PortfolioUpdate),
+}
+
+pub struct App {
+    client: PfoClient,
+    store: Store,
+    options: UpdateOptions,
+    pub portfolios: TablePane<Portfolio>,
+    pub holdings: TablePane<PortfolioFundPrice>,
+    pub stats: TablePane<FundPriceStats>,
+    pub predictions: TablePane<PortfolioFundPredictionAllocation>,
+    pub focus: Pane,
+    pub mode: Mode,
+    pub status: String,
+    pub quit: bool,
+    portfolio: Option<Uuid>,
+}
+
+impl App {
+    pub fn new(client: PfoClient, store: Store, options: UpdateOptions) -> Self {
+        Self {
+            client,
+            store,
+            options,
+            portfolios: TablePane::new("Portfolios"),
+            holdings: TablePane::new("Holdings"),
+            stats: TablePane::new("Price Stats"),
+            predictions: TablePane::new("Predictions"),
+            focus: Pane::Portfolios,
+            mode: Mode::Normal,
+            status: String::new(),
+            quit: false,
+            portfolio: None,
+        }
+    }
+
+    pub fn help(&self) -> &'static str {
+        match self.mode {
+            Mode::Normal => {
+                "q quit | tab pane | ↑↓ select | enter open | s sort | r reverse | c columns | a add | e edit | d remove | p predict | R refresh"
+            }
+            Mode::Form(_) => "tab next field | enter save | esc cancel",
+            Mode::Budget(_) => "enter predict | esc cancel",
+            Mode::ConfirmUpdate(..) => "y update | any other key cancel",
+            Mode::Preview(_) => "any key close",
+        }
+    }
+
+    /// Handle a key press, returning work to do with the server if any
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Form(mut form) => match form.handle_key(key) {
+                FormEvent::Editing => {
+                    self.mode = Mode::Form(form);
+                    None
+                }
+                FormEvent::Cancelled => None,
+                FormEvent::Submitted => match (self.portfolio, form.to_update()) {
+                    (Some(id), Ok(update)) => Some(Action::Update(id, update)),
+                    (_, Err(err)) => {
+                        self.status = err;
+                        self.mode = Mode::Form(form);
+                        None
+                    }
+                    (None, _) => None,
+                },
+            },
+            Mode::Budget(mut budget) => match key.code {
+                KeyCode::Esc => None,
+                KeyCode::Enter => match (self.portfolio, budget.trim().parse::<f32>()) {
+                    (Some(id), Ok(budget)) => Some(Action::Predict(id, budget)),
+                    (_, Err(_)) => {
+                        self.status = format!("Invalid budget: {}", budget);
+                        self.mode = Mode::Budget(budget);
+                        None
+                    }
+                    (None, _) => None,
+                },
+                KeyCode::Backspace => {
+                    budget.pop();
+                    self.mode = Mode::Budget(budget);
+                    None
+                }
+                KeyCode::Char(c) => {
+                    budget.push(c);
+                    self.mode = Mode::Budget(budget);
+                    None
+                }
+                _ => {
+                    self.mode = Mode::Budget(budget);
+                    None
+                }
+            },
+            Mode::ConfirmUpdate(id, update, _) => match key.code {
+                KeyCode::Char('y') => Some(Action::Send(id, update)),
+                _ => {
+                    self.status = "Aborted".to_string();
+                    None
+                }
+            },
+            Mode::Preview(_) => None,
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::BackTab => self.focus = self.focus.previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.focused().select_next(),
+            KeyCode::Up | KeyCode::Char('k') => self.focused().select_previous(),
+            KeyCode::Char('s') => self.focused().sort_next_column(),
+            KeyCode::Char('r') => self.focused().reverse_sort(),
+            KeyCode::Char('c') => self.focused().toggle_columns(),
+            KeyCode::Enter if self.focus == Pane::Portfolios => {
+                return self
+                    .portfolios
+                    .selected()
+                    .map(|p| Action::LoadPortfolio(p.id));
+            }
+            KeyCode::Char('R') => {
+                return Some(match self.portfolio {
+                    Some(id) => Action::LoadPortfolio(id),
+                    None => Action::LoadPortfolios,
+                });
+            }
+            KeyCode::Char('a') if self.portfolio.is_some() => {
+                self.mode = Mode::Form(Box::new(FundForm::add()));
+            }
+            KeyCode::Char('e') if self.focus == Pane::Holdings => {
+                if let Some(fund) = self.holdings.selected() {
+                    self.mode = Mode::Form(Box::new(FundForm::edit(fund)));
+                }
+            }
+            KeyCode::Char('d') if self.focus == Pane::Holdings => {
+                return self
+                    .portfolio
+                    .zip(self.holdings.selected())
+                    .map(|(id, fund)| {
+                        Action::Update(
+                            id,
+                            PortfolioUpdate {
+                                add_codes: HashSet::new(),
+                                remove_codes: HashSet::from([fund.code.clone()]),
+                            },
+                        )
+                    });
+            }
+            KeyCode::Char('p') if self.portfolio.is_some() => {
+                self.mode = Mode::Budget(String::new());
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    pub fn focused(&mut self) -> &mut dyn TableView {
+        match self.focus {
+            Pane::Portfolios => &mut self.portfolios,
+            Pane::Holdings => &mut self.holdings,
+            Pane::Stats => &mut self.stats,
+            Pane::Predictions => &mut self.predictions,
+        }
+    }
+
+    pub fn pending_status(action: &Action) -> String {
+        match action {
+            Action::LoadPortfolios => "Loading portfolios...".to_string(),
+            Action::LoadPortfolio(id) => format!("Loading portfolio {}...", id),
+            Action::Predict(_, budget) => format!("Predicting purchases for {}...", budget),
+            Action::Update(..) | Action::Send(..) => "Updating portfolio...".to_string(),
+        }
+    }
+
+    /// Do `action`, showing the outcome or error in the status line
+    pub async fn perform(&mut self, action: Action) {
+        self.status = match self.try_perform(action).await {
+            Ok(status) => status,
+            Err(err) => format!("Error: {:#}", err),
+        };
+    }
+
+    async fn try_perform(&mut self, action: Action) -> Result<String> {
+        match action {
+            Action::LoadPortfolios => {
+                self.portfolios
+                    .set_items(self.client.list_portfolios().await?);
+                Ok(format!(
+                    "Loaded {} portfolios",
+                    self.portfolios.items().len()
+                ))
+            }
+            Action::LoadPortfolio(id) => {
+                self.load_portfolio(id).await?;
+                Ok(format!("Loaded {} funds", self.holdings.items().len()))
+            }
+            Action::Predict(id, budget) => {
+                let predictions = self
+                    .client
+                    .get_portfolio_fund_predictions(id, budget)
+                    .await?;
+                let allocations = PortfolioFundPredictionAllocation::from_predictions(
+                    &predictions,
+                    self.holdings.items(),
+                );
+                let cost: f64 = allocations.iter().map(|a| a.cost).sum();
+                self.predictions.set_items(allocations);
+                self.focus = Pane::Predictions;
+                Ok(format!(
+                    "Total cost: {:.6}, leftover: {:.6}",
+                    cost,
+                    budget as f64 - cost
+                ))
+            }
+            Action::Update(id, update) => {
+                match check_update(&self.client, id, &update, self.options).await? {
+                    UpdateReview::Send => {}
+                    UpdateReview::Preview(preview) => {
+                        self.mode = Mode::Preview(preview);
+                        return Ok("Dry run, portfolio was not updated".to_string());
+                    }
+                    UpdateReview::Confirm(removals) => {
+                        self.mode = Mode::ConfirmUpdate(id, update, removals);
+                        return Ok("Funds to remove still have owned units".to_string());
+                    }
+                }
+
+                send_update(&self.client, &mut self.store, id, update, &[]).await?;
+                self.load_portfolio(id).await?;
+                Ok("Successfully updated portfolio, undo with `pfo portfolio undo`".to_string())
+            }
+            Action::Send(id, update) => {
+                send_update(&self.client, &mut self.store, id, update, &[]).await?;
+                self.load_portfolio(id).await?;
+                Ok("Successfully updated portfolio, undo with `pfo portfolio undo`".to_string())
+            }
+        }
+    }
+
+    async fn load_portfolio(&mut self, id: Uuid) -> Result<()> {
+        let prices = self
+            .client
+            .get_portfolio_fund_prices(id, None, None)
+            .await?;
+        let stats = self.client.get_portfolio_fund_price_stats(id, None).await?;
+
+        if self.portfolio != Some(id) {
+            self.predictions.clear();
+        }
+        self.portfolio = Some(id);
+        self.holdings.set_items(prices);
+        self.stats.set_items(stats);
+        if self.focus == Pane::Portfolios {
+            self.focus = Pane::Holdings;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pfo_mock::{Fixtures, MockServer};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const MAIN: Uuid = Uuid::from_u128(0x11111111_1111_1111_1111_111111111111);
+    const SECOND: Uuid = Uuid::from_u128(0x22222222_2222_2222_2222_222222222222);
+
+    /// App talking to a mock server, with a local database of its own
+    struct TestApp {
+        app: App,
+        _server: MockServer,
+        _dir: TempDir,
+    }
+
+    impl TestApp {
+        fn new(options: UpdateOptions) -> Self {
+            let server = MockServer::start(Fixtures::bundled()).unwrap();
+            let dir = TempDir::new().unwrap();
+            let client = PfoClient::new("127.0.0.1", server.port()).unwrap();
+            let store = Store::open(Some(&dir.path().join("pfo.db"))).unwrap();
+
+            Self {
+                app: App::new(client, store, options),
+                _server: server,
+                _dir: dir,
+            }
+        }
+
+        /// Open portfolio `id` and select holding `code`
+        async fn select(&mut self, id: Uuid, code: &str) {
+            self.app.perform(Action::LoadPortfolio(id)).await;
+            assert_eq!(self.app.focus, Pane::Holdings);
+            while self.app.holdings.selected().unwrap().code != code {
+                self.app.focused().select_next();
+            }
+        }
+
+        /// Press `c`, performing work it asks for
+        async fn press(&mut self, c: char) {
+            if let Some(action) = self.app.handle_key(KeyEvent::from(KeyCode::Char(c))) {
+                self.app.perform(action).await;
+            }
+        }
+
+        fn holdings(&self) -> Vec<&str> {
+            let mut codes: Vec<&str> = self
+                .app
+                .holdings
+                .items()
+                .iter()
+                .map(|h| h.code.as_str())
+                .collect();
+            codes.sort();
+            codes
+        }
+    }
+
+    #[test]
+    fn tab_cycles_focus() {
+        let mut focus = Pane::Portfolios;
+        for expected in [
+            Pane::Holdings,
+            Pane::Stats,
+            Pane::Predictions,
+            Pane::Portfolios,
+        ] {
+            focus = focus.next();
+            assert_eq!(focus, expected);
+        }
+        assert_eq!(focus.previous(), Pane::Predictions);
+    }
+
+    #[tokio::test]
+    async fn removal_of_owned_fund_is_confirmed() {
+        let mut test = TestApp::new(UpdateOptions::default());
+        test.select(MAIN, "AAA").await;
+
+        test.press('d').await;
+        assert!(matches!(
+            &test.app.mode,
+            Mode::ConfirmUpdate(_, _, removals) if removals.len() == 1 && removals[0].starts_with("- AAA owned: 10")
+        ));
+        test.press('n').await;
+        assert!(matches!(test.app.mode, Mode::Normal));
+        assert_eq!(test.app.status, "Aborted");
+        assert_eq!(test.holdings(), ["AAA", "BBB"]);
+
+        test.press('d').await;
+        test.press('y').await;
+        assert!(matches!(test.app.mode, Mode::Normal));
+        assert_eq!(test.holdings(), ["BBB"]);
+    }
+
+    #[tokio::test]
+    async fn removal_of_fund_without_units_is_not_confirmed() {
+        let mut test = TestApp::new(UpdateOptions::default());
+        test.select(SECOND, "AAA").await;
+
+        test.press('d').await;
+
+        assert!(matches!(test.app.mode, Mode::Normal));
+        assert_eq!(test.holdings(), ["CCC"]);
+    }
+
+    #[tokio::test]
+    async fn yes_skips_confirmation() {
+        let options = UpdateOptions {
+            dry_run: false,
+            yes: true,
+        };
+        let mut test = TestApp::new(options);
+        test.select(MAIN, "AAA").await;
+
+        test.press('d').await;
+
+        assert!(matches!(test.app.mode, Mode::Normal));
+        assert_eq!(test.holdings(), ["BBB"]);
+    }
+
+    #[tokio::test]
+    async fn dry_run_previews_update_without_sending_it() {
+        let options = UpdateOptions {
+            dry_run: true,
+            yes: false,
+        };
+        let mut test = TestApp::new(options);
+        test.select(MAIN, "AAA").await;
+
+        test.press('d').await;
+        assert!(matches!(
+            &test.app.mode,
+            Mode::Preview(preview) if preview.contains("\"AAA\"") && preview.contains("- AAA owned: 10")
+        ));
+        test.press('x').await;
+        assert!(matches!(test.app.mode, Mode::Normal));
+
+        test.app.perform(Action::LoadPortfolio(MAIN)).await;
+        assert_eq!(test.holdings(), ["AAA", "BBB"]);
+    }
+}
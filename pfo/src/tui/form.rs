@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+use crate::portfolio::{PortfolioFundPrice, PortfolioFundUpdate, PortfolioUpdate};
+
+/// Result of passing a key to a [`FundForm`]
+pub enum FormEvent {
+    Editing,
+    Cancelled,
+    Submitted,
+}
+
+/// Popup for adding a fund to a portfolio or editing settings of a fund already in it
+pub struct FundForm {
+    current: Option<PortfolioFundPrice>,
+    fields: [(&'static str, String); 5],
+    focused: usize,
+}
+
+const CODE: usize = 0;
+
+impl FundForm {
+    pub fn add() -> Self {
+        Self {
+            current: None,
+            fields: [
+                ("Code", String::new()),
+                ("Weight", String::new()),
+                ("Min Amount", String::new()),
+                ("Owned", String::new()),
+                ("Money Spent", String::new()),
+            ],
+            focused: CODE,
+        }
+    }
+
+    /// Form prefilled with settings of `current`. Weight is left empty since server only reports
+    /// normalized weights.
+    pub fn edit(current: &PortfolioFundPrice) -> Self {
+        Self {
+            fields: [
+                ("Code", current.code.clone()),
+                ("Weight", String::new()),
+                ("Min Amount", current.min_amount.to_string()),
+                ("Owned", current.owned_amount.to_string()),
+                ("Money Spent", format!("{:.6}", current.money_spent)),
+            ],
+            current: Some(current.clone()),
+            focused: 1,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match &self.current {
+            Some(current) => format!(
+                "Edit {} (normalized weight {:.2})",
+                current.code, current.normalized_weight
+            ),
+            None => "Add fund".to_string(),
+        }
+    }
+
+    /// Label, value and whether the field is focused, for each field
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str, bool)> {
+        self.fields
+            .iter()
+            .enumerate()
+            .map(|(i, (label, value))| (*label, value.as_str(), i == self.focused))
+    }
+
+    fn first_field(&self) -> usize {
+        if self.current.is_some() { 1 } else { CODE }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> FormEvent {
+        match key.code {
+            KeyCode::Esc => return FormEvent::Cancelled,
+            KeyCode::Enter => return FormEvent::Submitted,
+            KeyCode::Tab | KeyCode::Down => {
+                self.focused = if self.focused + 1 < self.fields.len() {
+                    self.focused + 1
+                } else {
+                    self.first_field()
+                };
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focused = if self.focused > self.first_field() {
+                    self.focused - 1
+                } else {
+                    self.fields.len() - 1
+                };
+            }
+            KeyCode::Backspace => {
+                self.fields[self.focused].1.pop();
+            }
+            KeyCode::Char(c) => self.fields[self.focused].1.push(c),
+            _ => {}
+        }
+
+        FormEvent::Editing
+    }
+
+    fn parse<T: FromStr>(&self, i: usize) -> Result<Option<T>, String> {
+        let (label, value) = &self.fields[i];
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+
+        value
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("Invalid {}: {}", label.to_lowercase(), value))
+    }
+
+    /// Update adding the fund, or changing only the settings that differ from current ones
+    pub fn to_update(&self) -> Result<PortfolioUpdate, String> {
+        let code = self.fields[CODE].1.trim().to_uppercase();
+        if code.is_empty() {
+            return Err("Fund code is required".to_string());
+        }
+
+        let changed =
+            |value: Option<u32>, current: Option<u32>| value.filter(|v| Some(*v) != current);
+        let current = self.current.as_ref();
+        let spent: Option<f64> = self.parse(4)?;
+
+        let fund = PortfolioFundUpdate {
+            fund_code: code,
+            weight: self.parse(1)?,
+            min_amount: changed(self.parse(2)?, current.map(|c| c.min_amount)),
+            owned_amount: changed(self.parse(3)?, current.map(|c| c.owned_amount)),
+            total_money_spent: spent.filter(|s| {
+                current.is_none_or(|c| format!("{:.6}", s) != format!("{:.6}", c.money_spent))
+            }),
+        };
+        if current.is_some()
+            && fund.weight.is_none()
+            && fund.min_amount.is_none()
+            && fund.owned_amount.is_none()
+            && fund.total_money_spent.is_none()
+        {
+            return Err("Nothing changed".to_string());
+        }
+
+        Ok(PortfolioUpdate {
+            add_codes: HashSet::from([fund]),
+            remove_codes: HashSet::new(),
+        })
+    }
+}
@@ -0,0 +1,55 @@
+mod app;
+mod form;
+mod table;
+mod ui;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use pfo_client::PfoClient;
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use crate::cli::UpdateOptions;
+use crate::store::Store;
+use crate::tui::app::{Action, App};
+
+async fn run_app(terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
+    let mut pending = Some(Action::LoadPortfolios);
+
+    while !app.quit {
+        if let Some(action) = pending.take() {
+            app.status = App::pending_status(&action);
+            terminal.draw(|frame| ui::draw(frame, app))?;
+            app.perform(action).await;
+        }
+
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        if event::poll(Duration::from_millis(250))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            pending = app.handle_key(key);
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the full-screen dashboard until the user quits
+pub async fn run(
+    client: PfoClient,
+    database: Option<PathBuf>,
+    options: UpdateOptions,
+) -> Result<()> {
+    let store = Store::open(database.as_deref())?;
+    let mut app = App::new(client, store, options);
+
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, &mut app).await;
+    ratatui::restore();
+
+    result
+}
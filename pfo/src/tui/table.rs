@@ -0,0 +1,257 @@
+use std::cmp::Ordering;
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use pfo_core::output::{ColumnEnum, RowStruct, Table};
+use pfo_core::parse_naive_date;
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Constraint, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Row, TableState};
+
+/// Value of a cell as compared when sorting, missing values first
+enum SortKey<'a> {
+    Missing,
+    Number(f64),
+    Date(NaiveDate),
+    Text(&'a str),
+}
+
+impl<'a> SortKey<'a> {
+    fn new(value: &'a str) -> Self {
+        if value == "-" {
+            SortKey::Missing
+        } else if let Ok(number) = value.parse() {
+            SortKey::Number(number)
+        } else if let Ok(date) = parse_naive_date(value) {
+            SortKey::Date(date)
+        } else {
+            SortKey::Text(value)
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            SortKey::Missing => 0,
+            SortKey::Number(_) => 1,
+            SortKey::Date(_) => 2,
+            SortKey::Text(_) => 3,
+        }
+    }
+
+    fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Date(a), SortKey::Date(b)) => a.cmp(b),
+            (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+/// A pane showing a list of `T` with the columns, headers and alignment derived for its table
+pub struct TablePane<T: Table> {
+    title: &'static str,
+    items: Vec<T>,
+    columns: Vec<T::ColumnEnum>,
+    all_columns: bool,
+    sort: Option<(usize, bool)>,
+    state: TableState,
+}
+
+impl<T> TablePane<T>
+where
+    T: Table,
+    T::RowStruct: RowStruct<ColumnEnum = T::ColumnEnum, Target = T>,
+{
+    pub fn new(title: &'static str) -> Self {
+        Self {
+            title,
+            items: Vec::new(),
+            columns: T::ColumnEnum::default_columns(),
+            all_columns: false,
+            sort: None,
+            state: TableState::default(),
+        }
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn set_items(&mut self, items: Vec<T>) {
+        self.items = items;
+        self.apply_sort();
+        self.state.select((!self.items.is_empty()).then_some(0));
+    }
+
+    pub fn clear(&mut self) {
+        self.set_items(Vec::new());
+    }
+
+    pub fn selected(&self) -> Option<&T> {
+        self.state.selected().and_then(|i| self.items.get(i))
+    }
+
+    fn apply_sort(&mut self) {
+        let Some((i, descending)) = self.sort else {
+            return;
+        };
+        let column = &self.columns[i];
+
+        let selected = self.state.selected();
+        let mut keyed: Vec<(String, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let row = T::RowStruct::from_value(item, true);
+                (row.value_from_col(column).to_string(), index)
+            })
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| {
+            let ordering = SortKey::new(a).compare(&SortKey::new(b));
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        let mut items: Vec<Option<T>> = std::mem::take(&mut self.items)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.items = keyed
+            .iter()
+            .map(|(_, index)| items[*index].take().expect("every item is taken once"))
+            .collect();
+        if let Some(selected) = selected {
+            self.state
+                .select(keyed.iter().position(|(_, index)| *index == selected));
+        }
+    }
+}
+
+/// Navigation and rendering of a pane, independent of the type of its items
+pub trait TableView {
+    fn select_next(&mut self);
+
+    fn select_previous(&mut self);
+
+    /// Switch between default columns and every column of the table
+    fn toggle_columns(&mut self);
+
+    /// Sort by the next visible column, going back to server order after the last one
+    fn sort_next_column(&mut self);
+
+    fn reverse_sort(&mut self);
+
+    fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool);
+}
+
+impl<T> TableView for TablePane<T>
+where
+    T: Table,
+    T::RowStruct: RowStruct<ColumnEnum = T::ColumnEnum, Target = T>,
+{
+    fn select_next(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some((i + 1).min(self.items.len() - 1)));
+        }
+    }
+
+    fn select_previous(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some(i.saturating_sub(1)));
+        }
+    }
+
+    fn toggle_columns(&mut self) {
+        self.all_columns = !self.all_columns;
+        self.columns = if self.all_columns {
+            T::ColumnEnum::value_variants().to_vec()
+        } else {
+            T::ColumnEnum::default_columns()
+        };
+        self.sort = None;
+    }
+
+    fn sort_next_column(&mut self) {
+        self.sort = match self.sort {
+            None => Some((0, false)),
+            Some((i, _)) if i + 1 < self.columns.len() => Some((i + 1, false)),
+            Some(_) => None,
+        };
+        self.apply_sort();
+    }
+
+    fn reverse_sort(&mut self) {
+        if let Some((_, descending)) = &mut self.sort {
+            *descending = !*descending;
+        }
+        self.apply_sort();
+    }
+
+    fn render(&mut self, frame: &mut Frame, area: Rect, focused: bool) {
+        let rows: Vec<T::RowStruct> = self
+            .items
+            .iter()
+            .map(|item| T::RowStruct::from_value(item, false))
+            .collect();
+
+        let header = Row::new(self.columns.iter().enumerate().map(|(i, column)| {
+            let marker = match self.sort {
+                Some((sorted, false)) if sorted == i => " ▲",
+                Some((sorted, true)) if sorted == i => " ▼",
+                _ => "",
+            };
+            Cell::from(format!("{}{}", column.header(), marker))
+        }))
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let widths: Vec<Constraint> = self
+            .columns
+            .iter()
+            .map(|column| {
+                let width = rows
+                    .iter()
+                    .map(|row| row.len_from_col(column))
+                    .chain([column.header().len() + 2])
+                    .max()
+                    .unwrap_or_default();
+                Constraint::Length(width as u16)
+            })
+            .collect();
+
+        let body = rows.iter().map(|row| {
+            Row::new(self.columns.iter().map(|column| {
+                let alignment = if column.left_align() {
+                    Alignment::Left
+                } else {
+                    Alignment::Right
+                };
+                Cell::from(Line::from(row.value_from_col(column).to_string()).alignment(alignment))
+            }))
+        });
+
+        let border_style = if focused {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default()
+        };
+        let table = ratatui::widgets::Table::new(body, widths)
+            .header(header)
+            .column_spacing(T::COLUMN_SPACING as u16)
+            .block(
+                Block::default()
+                    .title(self.title)
+                    .borders(Borders::ALL)
+                    .border_style(border_style),
+            )
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
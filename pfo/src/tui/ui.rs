@@ -0,0 +1,98 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::tui::app::{App, Mode, Pane};
+use crate::tui::table::TableView;
+
+fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+fn draw_popup(frame: &mut Frame, title: String, lines: Vec<Line>) {
+    let area = popup_area(frame.area(), 60, lines.len() as u16 + 2);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL)),
+        area,
+    );
+}
+
+/// Portfolio list on the left, holdings, price stats and predictions stacked on the right
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [main, status, help] = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [portfolios, right] =
+        Layout::horizontal([Constraint::Percentage(25), Constraint::Percentage(75)]).areas(main);
+    let [holdings, stats, predictions] = Layout::vertical([
+        Constraint::Percentage(40),
+        Constraint::Percentage(30),
+        Constraint::Percentage(30),
+    ])
+    .areas(right);
+
+    let focus = app.focus;
+    app.portfolios
+        .render(frame, portfolios, focus == Pane::Portfolios);
+    app.holdings
+        .render(frame, holdings, focus == Pane::Holdings);
+    app.stats.render(frame, stats, focus == Pane::Stats);
+    app.predictions
+        .render(frame, predictions, focus == Pane::Predictions);
+
+    frame.render_widget(Paragraph::new(app.status.as_str()), status);
+    frame.render_widget(
+        Paragraph::new(app.help()).style(Style::default().add_modifier(Modifier::DIM)),
+        help,
+    );
+
+    match &app.mode {
+        Mode::Normal => {}
+        Mode::Form(form) => {
+            let lines = form
+                .fields()
+                .map(|(label, value, focused)| {
+                    let style = if focused {
+                        Style::default().add_modifier(Modifier::REVERSED)
+                    } else {
+                        Style::default()
+                    };
+                    Line::from(vec![
+                        Span::raw(format!("{:<12}", label)),
+                        Span::styled(format!("{:<40}", value), style),
+                    ])
+                })
+                .collect();
+            draw_popup(frame, form.title(), lines);
+        }
+        Mode::Budget(budget) => draw_popup(
+            frame,
+            "Predictions".to_string(),
+            vec![Line::from(format!("Budget: {}", budget))],
+        ),
+        Mode::ConfirmUpdate(_, _, removals) => {
+            let mut lines: Vec<Line> = removals.iter().map(|r| Line::from(r.as_str())).collect();
+            lines.push(Line::from(
+                "Remove funds that still have owned units? [y/N]",
+            ));
+            draw_popup(frame, "Update portfolio".to_string(), lines);
+        }
+        Mode::Preview(preview) => draw_popup(
+            frame,
+            "Dry run".to_string(),
+            preview.lines().map(Line::from).collect(),
+        ),
+    }
+}
@@ -0,0 +1,503 @@
+//! End-to-end tests running `pfo` against the mock server with its bundled fixtures
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use chrono::Local;
+use pfo_mock::{Fixtures, MockServer};
+use tempfile::TempDir;
+
+const MAIN: &str = "11111111-1111-1111-1111-111111111111";
+
+/// Mock server with a local database of its own
+struct Pfo {
+    server: MockServer,
+    dir: TempDir,
+}
+
+impl Pfo {
+    fn new() -> Self {
+        Self {
+            server: MockServer::start(Fixtures::bundled()).expect("mock server starts"),
+            dir: TempDir::new().expect("temporary directory is created"),
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        run(self.server.port(), &self.dir, args)
+    }
+
+    /// Output of a command reading `input` on stdin
+    fn run_with_input(&self, args: &[&str], input: &str) -> Output {
+        let mut child = command(self.server.port(), &self.dir, args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("pfo runs");
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(input.as_bytes())
+            .expect("input is written");
+        child.wait_with_output().expect("pfo runs")
+    }
+
+    /// Stdout of a command that is expected to succeed
+    fn stdout(&self, args: &[&str]) -> String {
+        let output = self.run(args);
+        assert!(
+            output.status.success(),
+            "pfo {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).expect("output is UTF-8")
+    }
+}
+
+fn command(port: u16, dir: &TempDir, args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_pfo"));
+    command
+        .args(["--port", &port.to_string(), "--retries", "0", "--database"])
+        .arg(dir.path().join("pfo.db"))
+        .args(args)
+        .env("PFO_LOG_LEVEL", "error");
+    command
+}
+
+fn run(port: u16, dir: &TempDir, args: &[&str]) -> Output {
+    command(port, dir, args).output().expect("pfo runs")
+}
+
+/// Cells of each row of a table, without headers
+fn rows(table: &str) -> Vec<Vec<&str>> {
+    table
+        .lines()
+        .skip(1)
+        .map(|line| line.split_whitespace().collect())
+        .collect()
+}
+
+/// First column of each row of a table, without headers
+fn first_column(table: &str) -> Vec<&str> {
+    rows(table)
+        .into_iter()
+        .filter_map(|row| row.first().copied())
+        .collect()
+}
+
+#[test]
+fn lists_portfolios() {
+    let pfo = Pfo::new();
+
+    let out = pfo.stdout(&["portfolio", "list", "--no-headers"]);
+
+    assert_eq!(
+        out.split_whitespace().collect::<Vec<_>>(),
+        [
+            MAIN,
+            "Main",
+            "22222222-2222-2222-2222-222222222222",
+            "Second"
+        ]
+    );
+}
+
+#[test]
+fn sorts_portfolio_fund_prices() {
+    let pfo = Pfo::new();
+
+    let desc = pfo.stdout(&["portfolio", "prices", MAIN, "-s", "price desc"]);
+    let asc = pfo.stdout(&["portfolio", "prices", MAIN, "-s", "price asc"]);
+
+    assert_eq!(first_column(&desc), ["AAA", "BBB"]);
+    assert_eq!(first_column(&asc), ["BBB", "AAA"]);
+}
+
+#[test]
+fn gets_portfolio_fund_prices_on_date() {
+    let pfo = Pfo::new();
+
+    let out = pfo.stdout(&[
+        "portfolio",
+        "prices",
+        MAIN,
+        "-d",
+        "02.01.2024",
+        "-o",
+        "code,date,price",
+    ]);
+
+    assert_eq!(
+        rows(&out),
+        [
+            ["AAA", "02.01.2024", "9.500000"],
+            ["BBB", "02.01.2024", "5.200000"]
+        ]
+    );
+}
+
+#[test]
+fn filters_funds_by_codes_and_date() {
+    let pfo = Pfo::new();
+
+    let latest = pfo.stdout(&["fund", "get", "CCC,AAA", "-s", "code asc"]);
+    let before = pfo.stdout(&["fund", "get", "AAA", "-d", "01.15.2024"]);
+
+    assert_eq!(first_column(&latest), ["AAA", "CCC"]);
+    assert!(latest.contains("03.01.2024"), "{}", latest);
+    assert!(first_column(&before).is_empty(), "{}", before);
+}
+
+#[test]
+fn sorts_fund_price_stats() {
+    let pfo = Pfo::new();
+
+    let all = pfo.stdout(&["fund", "price-stats", "-s", "monthly-return desc"]);
+    let portfolio = pfo.stdout(&["portfolio", "price-stats", MAIN, "-s", "yearly-return asc"]);
+
+    assert_eq!(first_column(&all), ["CCC", "AAA", "BBB"]);
+    assert_eq!(first_column(&portfolio), ["BBB", "AAA"]);
+}
+
+#[test]
+fn gets_predictions_for_budget() {
+    let pfo = Pfo::new();
+
+    let out = pfo.stdout(&[
+        "portfolio",
+        "predictions",
+        MAIN,
+        "-b",
+        "1000",
+        "-o",
+        "code,amount",
+    ]);
+
+    assert_eq!(rows(&out)[..2], [["AAA", "66"], ["BBB", "66"]]);
+}
+
+#[test]
+fn budget_range_includes_its_end() {
+    let pfo = Pfo::new();
+
+    let out = pfo.stdout(&[
+        "portfolio",
+        "predictions",
+        MAIN,
+        "--budget-range",
+        "0..0.3",
+        "--step",
+        "0.1",
+        "--csv",
+        "--no-headers",
+    ]);
+
+    let budgets: Vec<&str> = out.lines().filter_map(|l| l.split(',').next()).collect();
+    assert_eq!(budgets, ["0.00", "0.10", "0.20", "0.30"]);
+}
+
+#[test]
+fn budget_range_with_too_many_budgets_is_rejected() {
+    let pfo = Pfo::new();
+
+    let output = pfo.run(&[
+        "portfolio",
+        "predictions",
+        MAIN,
+        "--budget-range",
+        "0..1e9",
+        "--step",
+        "0.01",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("more than 10000 budgets"));
+}
+
+#[test]
+fn added_fund_is_listed() {
+    let pfo = Pfo::new();
+
+    pfo.stdout(&[
+        "--yes",
+        "portfolio",
+        "add",
+        MAIN,
+        "-c",
+        "CCC",
+        "-w",
+        "1",
+        "-o",
+        "4",
+    ]);
+    let out = pfo.stdout(&["portfolio", "prices", MAIN, "-o", "code,owned-amount"]);
+
+    assert_eq!(rows(&out), [["AAA", "10"], ["BBB", "20"], ["CCC", "4"]]);
+}
+
+#[test]
+fn trade_of_zero_units_is_rejected() {
+    let pfo = Pfo::new();
+
+    let output = pfo.run(&["--yes", "portfolio", "buy", MAIN, "AAA", "-u", "0"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn sell_of_fund_without_units_is_rejected() {
+    let pfo = Pfo::new();
+    let second = "22222222-2222-2222-2222-222222222222";
+
+    let output = pfo.run(&["--yes", "portfolio", "sell", second, "AAA", "-u", "1"]);
+
+    assert_eq!(output.status.code(), Some(8));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no units are owned"));
+}
+
+#[test]
+fn fund_not_in_portfolio_is_not_found() {
+    let pfo = Pfo::new();
+
+    let set = pfo.run(&["--yes", "portfolio", "set", MAIN, "CCC", "-w", "2"]);
+    let buy = pfo.run(&["--yes", "portfolio", "buy", MAIN, "CCC", "-u", "1"]);
+
+    assert_eq!(set.status.code(), Some(6));
+    assert_eq!(buy.status.code(), Some(6));
+    assert!(String::from_utf8_lossy(&buy.stderr).contains("give the price with --unit-price"));
+}
+
+#[test]
+fn ledger_transactions_without_units_are_rejected() {
+    let pfo = Pfo::new();
+    let file = pfo.dir.path().join("transactions.csv");
+    std::fs::write(
+        &file,
+        "code,date,units,unit_price\nAAA,02.01.2024,3,9.5\nBBB,02.01.2024,0,5.2\n",
+    )
+    .expect("CSV is written");
+
+    let add = pfo.run(&[
+        "portfolio",
+        "ledger",
+        "add",
+        MAIN,
+        "-c",
+        "AAA",
+        "-d",
+        "02.01.2024",
+        "-u",
+        "0",
+        "-P",
+        "9.5",
+    ]);
+    let import = pfo.run(&[
+        "portfolio",
+        "ledger",
+        "import",
+        MAIN,
+        file.to_str().expect("path is UTF-8"),
+    ]);
+
+    assert_eq!(add.status.code(), Some(2));
+    assert_eq!(import.status.code(), Some(8));
+    assert!(String::from_utf8_lossy(&import.stderr).contains("row 2"));
+    let ledger = pfo.stdout(&["portfolio", "ledger", "list", MAIN, "--no-headers"]);
+    assert_eq!(ledger.trim(), "");
+}
+
+#[test]
+fn undo_of_buy_removes_its_transaction() {
+    let pfo = Pfo::new();
+
+    pfo.stdout(&["--yes", "portfolio", "buy", MAIN, "AAA", "-u", "2"]);
+    pfo.stdout(&[
+        "--yes",
+        "portfolio",
+        "add",
+        MAIN,
+        "-c",
+        "BBB",
+        "--min-amount",
+        "2",
+    ]);
+    let ledger = pfo.stdout(&["portfolio", "ledger", "list", MAIN, "-o", "code,units"]);
+    assert_eq!(rows(&ledger), [["AAA", "2"]]);
+
+    let out = pfo.stdout(&["--yes", "portfolio", "undo", MAIN, "2"]);
+
+    assert!(out.contains("Removed 1 ledger transactions recorded for it"));
+    let ledger = pfo.stdout(&["portfolio", "ledger", "list", MAIN, "--no-headers"]);
+    assert_eq!(ledger.trim(), "");
+    let prices = pfo.stdout(&[
+        "portfolio",
+        "prices",
+        MAIN,
+        "-o",
+        "code,min-amount,owned-amount",
+    ]);
+    assert_eq!(rows(&prices), [["AAA", "1", "10"], ["BBB", "0", "20"]]);
+}
+
+#[test]
+fn diff_from_date_uses_latest_snapshot_before_it() {
+    let pfo = Pfo::new();
+    let today = Local::now().format("%m.%d.%Y").to_string();
+
+    pfo.stdout(&["portfolio", "snapshot", MAIN]);
+    pfo.stdout(&["--yes", "portfolio", "buy", MAIN, "AAA", "-u", "2"]);
+    let out = pfo.stdout(&[
+        "portfolio",
+        "diff",
+        MAIN,
+        "--from",
+        &today,
+        "-o",
+        "code,owned-change,from-price,from-value",
+    ]);
+
+    assert_eq!(
+        rows(&out)[..2],
+        [
+            ["AAA", "2", "10.000000", "100.000000"],
+            ["BBB", "0", "5.000000", "100.000000"]
+        ]
+    );
+}
+
+#[test]
+fn diff_from_date_without_snapshot_fails() {
+    let pfo = Pfo::new();
+
+    pfo.stdout(&["portfolio", "snapshot", MAIN]);
+    let output = pfo.run(&["portfolio", "diff", MAIN, "--from", "02.01.2024"]);
+
+    assert_eq!(output.status.code(), Some(6));
+}
+
+#[test]
+fn drift_out_of_band_fails_after_report() {
+    let pfo = Pfo::new();
+
+    let output = pfo.run(&["portfolio", "drift", MAIN, "-o", "code,out-of-band"]);
+
+    assert_eq!(output.status.code(), Some(9));
+    assert_eq!(
+        rows(&String::from_utf8_lossy(&output.stdout)),
+        [["AAA", "true"], ["BBB", "true"]]
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("2 funds are out of tolerance band"));
+}
+
+#[test]
+fn unknown_portfolio_fails_with_problem_detail() {
+    let pfo = Pfo::new();
+    let id = "33333333-3333-3333-3333-333333333333";
+
+    let output = pfo.run(&["--format", "json", "portfolio", "get", id]);
+
+    assert_eq!(output.status.code(), Some(6));
+    let error: serde_json::Value =
+        serde_json::from_slice(&output.stderr).expect("error is printed as JSON");
+    assert_eq!(error["status"], 404);
+    assert_eq!(error["method"], "GET");
+    assert_eq!(error["instance"], format!("/p/{}", id));
+    assert_eq!(error["detail"], format!("Portfolio {} not found", id));
+}
+
+#[test]
+fn rejected_update_fails_with_status() {
+    let pfo = Pfo::new();
+
+    let output = pfo.run(&["--yes", "portfolio", "add", MAIN, "-c", "ZZZ"]);
+
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("400 Bad Request"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Fund ZZZ not found"));
+}
+
+#[test]
+fn shell_skips_blank_lines_and_comments() {
+    let pfo = Pfo::new();
+
+    let output = pfo.run_with_input(
+        &["shell"],
+        "# note\n   \nportfolio list --no-headers\nexit\n",
+    );
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains(MAIN));
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
+
+#[test]
+fn shell_prints_errors_in_global_format() {
+    let pfo = Pfo::new();
+    let id = "33333333-3333-3333-3333-333333333333";
+
+    let output = pfo.run_with_input(
+        &["--format", "json", "shell"],
+        &format!("portfolio get {}\nportfolio list\n", id),
+    );
+
+    assert!(output.status.success());
+    let error: serde_json::Value =
+        serde_json::from_slice(&output.stderr).expect("error is printed as JSON");
+    assert_eq!(error["status"], 404);
+    assert!(String::from_utf8_lossy(&output.stdout).contains(MAIN));
+}
+
+#[test]
+fn shell_gives_working_portfolio_only_to_commands_of_one_portfolio() {
+    let pfo = Pfo::new();
+
+    let output = pfo.run_with_input(
+        &["shell"],
+        "use Main\nportfolio prices -o code --no-headers\nportfolio holdings --no-headers\n",
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("AAA"), "{}", stdout);
+    assert!(stdout.contains("Second"), "{}", stdout);
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
+
+#[test]
+fn shell_rejects_global_options_fixed_at_start() {
+    let pfo = Pfo::new();
+
+    let output = pfo.run_with_input(
+        &["shell"],
+        "portfolio list --retries 5\n--format json portfolio list\nportfolio list --yes\n",
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--retries can not be changed in shell"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("--format can not be changed in shell"),
+        "{}",
+        stderr
+    );
+    assert_eq!(stderr.lines().count(), 2, "{}", stderr);
+    assert!(String::from_utf8_lossy(&output.stdout).contains(MAIN));
+}
+
+#[test]
+fn unreachable_server_fails_with_transport_error() {
+    let port = MockServer::start(Fixtures::bundled())
+        .expect("mock server starts")
+        .port();
+    let dir = TempDir::new().expect("temporary directory is created");
+
+    let output = run(port, &dir, &["portfolio", "list"]);
+
+    assert_eq!(output.status.code(), Some(3));
+}
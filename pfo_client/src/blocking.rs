@@ -0,0 +1,177 @@
+//! Client for programs that do not use async, enabled by the `blocking` feature
+//!
+//! ```no_run
+//! # fn run() -> pfo_client::Result<()> {
+//! let client = pfo_client::PfoClient::builder().port(8080).build_blocking()?;
+//! for fund in client.get_funds(Default::default(), None)? {
+//!     println!("{} {}", fund.code, fund.price);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::NaiveDate;
+use reqwest::Url;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::ACCEPT;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::client::Options;
+use crate::endpoint::Endpoint;
+use crate::error::{Error, Result};
+use crate::model::{
+    FundInfo, FundPriceStats, Portfolio, PortfolioFundPrediction, PortfolioFundPrice,
+    PortfolioUpdate,
+};
+use crate::query::{FundFilter, Sort};
+use crate::retry::Retries;
+
+/// Blocking counterpart of [`crate::PfoClient`], created by
+/// [`PfoClientBuilder::build_blocking`](crate::PfoClientBuilder::build_blocking). It must not be
+/// created or used within an async runtime.
+#[derive(Clone)]
+pub struct PfoClient {
+    client: Client,
+    url: Url,
+    options: Options,
+}
+
+impl PfoClient {
+    pub(crate) fn new(client: Client, url: Url, options: Options) -> Self {
+        Self {
+            client,
+            url,
+            options,
+        }
+    }
+
+    /// Client of the server at `host` and `port` with default options
+    pub fn connect(host: impl Into<String>, port: u16) -> Result<Self> {
+        crate::PfoClient::builder()
+            .host(host)
+            .port(port)
+            .build_blocking()
+    }
+
+    fn request<B: Serialize>(&self, endpoint: &Endpoint<B>) -> RequestBuilder {
+        let mut request = self
+            .client
+            .request(endpoint.method.clone(), endpoint.url(&self.url));
+
+        if endpoint.should_have_content {
+            request = request.header(ACCEPT, "application/json");
+        }
+
+        if let Some(body) = &endpoint.body {
+            request = request.json(body);
+        }
+
+        request
+    }
+
+    fn send<B: Serialize>(&self, endpoint: &Endpoint<B>) -> Result<Response> {
+        let (client, request) = self.request(endpoint).build_split();
+        let request = request.map_err(Error::Request)?;
+        let (method, url) = (request.method().clone(), request.url().clone());
+
+        let mut retries = Retries::new(&method, &url, &self.options);
+        let result = loop {
+            let Some(current) = retries.left().then(|| request.try_clone()).flatten() else {
+                break client.execute(request);
+            };
+
+            let result = client.execute(current);
+            match retries.delay(&result) {
+                Some(delay) => std::thread::sleep(delay),
+                None => break result,
+            }
+        };
+
+        let response = match result {
+            Ok(response) => response,
+            Err(err) => return Err(Error::from_send(method, url, err)),
+        };
+
+        let status = response.status().as_u16();
+        log::debug!("Got response {}", status);
+        if status >= 400 {
+            return match response.text() {
+                Ok(body) => Err(Error::from_status(method, url, status, body)),
+                Err(err) => Err(Error::from_send(method, url, err)),
+            };
+        }
+
+        Ok(response)
+    }
+
+    /// Send a request to `endpoint` and decode its JSON response
+    fn fetch<T: DeserializeOwned>(&self, endpoint: Endpoint) -> Result<T> {
+        self.send(&endpoint)?
+            .json()
+            .map_err(|source| Error::Decode {
+                what: endpoint.what,
+                source,
+            })
+    }
+
+    /// See [`crate::PfoClient::list_portfolios`]
+    pub fn list_portfolios(&self) -> Result<Vec<Portfolio>> {
+        self.fetch(Endpoint::list_portfolios())
+    }
+
+    /// See [`crate::PfoClient::get_portfolio`]
+    pub fn get_portfolio(&self, id: Uuid) -> Result<Portfolio> {
+        self.fetch(Endpoint::get_portfolio(id))
+    }
+
+    /// See [`crate::PfoClient::get_portfolio_fund_prices`]
+    pub fn get_portfolio_fund_prices(
+        &self,
+        id: Uuid,
+        date: Option<NaiveDate>,
+        sort: Option<Sort>,
+    ) -> Result<Vec<PortfolioFundPrice>> {
+        self.fetch(Endpoint::get_portfolio_fund_prices(id, date, sort))
+    }
+
+    /// See [`crate::PfoClient::get_portfolio_fund_price_stats`]
+    pub fn get_portfolio_fund_price_stats(
+        &self,
+        id: Uuid,
+        sort: Option<Sort>,
+    ) -> Result<Vec<FundPriceStats>> {
+        self.fetch(Endpoint::get_portfolio_fund_price_stats(id, sort))
+    }
+
+    /// See [`crate::PfoClient::get_portfolio_fund_predictions`]
+    pub fn get_portfolio_fund_predictions(
+        &self,
+        id: Uuid,
+        budget: f32,
+    ) -> Result<Vec<PortfolioFundPrediction>> {
+        self.fetch(Endpoint::get_portfolio_fund_predictions(id, budget))
+    }
+
+    /// See [`crate::PfoClient::update_portfolio`]
+    pub fn update_portfolio(&self, id: Uuid, update: PortfolioUpdate) -> Result<()> {
+        self.send(&Endpoint::update_portfolio(id, update))?;
+
+        Ok(())
+    }
+
+    /// See [`crate::PfoClient::get_funds`]
+    pub fn get_funds(&self, fund_filter: FundFilter, sort: Option<Sort>) -> Result<Vec<FundInfo>> {
+        self.fetch(Endpoint::get_funds(fund_filter, sort))
+    }
+
+    /// See [`crate::PfoClient::get_fund_price_stats`]
+    pub fn get_fund_price_stats(
+        &self,
+        codes: Vec<String>,
+        sort: Option<Sort>,
+    ) -> Result<Vec<FundPriceStats>> {
+        self.fetch(Endpoint::get_fund_price_stats(codes, sort))
+    }
+}
@@ -0,0 +1,369 @@
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use futures::{StreamExt, TryStreamExt, stream};
+use reqwest::header::ACCEPT;
+use reqwest::{Client, RequestBuilder, Response, Url};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::sync::Mutex;
+use tokio::time::{Interval, MissedTickBehavior};
+use uuid::Uuid;
+
+use crate::endpoint::Endpoint;
+use crate::error::{Error, Result};
+use crate::model::{
+    FundInfo, FundPriceStats, Portfolio, PortfolioFundPrediction, PortfolioFundPrice,
+    PortfolioUpdate,
+};
+use crate::query::{FundFilter, Sort};
+use crate::retry::Retries;
+
+/// Timeouts, retries and limits of requests sent by [`PfoClient`]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Options {
+    pub connect_timeout: Duration,
+    pub read_timeout: Duration,
+    pub retries: u32,
+    pub backoff: Duration,
+    pub max_in_flight: usize,
+    pub requests_per_second: Option<u32>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(30),
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            max_in_flight: 8,
+            requests_per_second: None,
+        }
+    }
+}
+
+/// Configuration of a [`PfoClient`], created by [`PfoClient::builder`]
+///
+/// ```no_run
+/// # async fn run() -> pfo_client::Result<()> {
+/// use std::time::Duration;
+///
+/// let client = pfo_client::PfoClient::builder()
+///     .host("pfo.local")
+///     .port(8080)
+///     .read_timeout(Duration::from_secs(5))
+///     .retries(1)
+///     .build()?;
+///
+/// for portfolio in client.list_portfolios().await? {
+///     println!("{} {}", portfolio.id, portfolio.name);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PfoClientBuilder {
+    host: String,
+    port: u16,
+    options: Options,
+}
+
+impl Default for PfoClientBuilder {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 8080,
+            options: Options::default(),
+        }
+    }
+}
+
+impl PfoClientBuilder {
+    /// Host of the server, `localhost` by default
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Port of the server, 8080 by default
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Longest wait for a connection to the server, 10 seconds by default
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.options.connect_timeout = timeout;
+        self
+    }
+
+    /// Longest wait for each read of a response, 30 seconds by default. The blocking client has no
+    /// timeout per read, it applies this to each whole request instead.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.options.read_timeout = timeout;
+        self
+    }
+
+    /// How many times a GET request is sent again after a connection error or a transient status,
+    /// 3 by default. Other requests are sent once.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.options.retries = retries;
+        self
+    }
+
+    /// Delay before the first retry, doubled for each following one and jittered, 500 ms by
+    /// default and at most 30 seconds. A `Retry-After` header of the response takes precedence,
+    /// within the same limit.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.options.backoff = backoff;
+        self
+    }
+
+    /// Most requests awaited at the same time by [`PfoClient::bulk`], 8 by default
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.options.max_in_flight = max_in_flight;
+        self
+    }
+
+    /// Most requests started each second by [`PfoClient::bulk`], unlimited by default
+    pub fn requests_per_second(mut self, requests_per_second: u32) -> Self {
+        self.options.requests_per_second = Some(requests_per_second);
+        self
+    }
+
+    pub fn build(self) -> Result<PfoClient> {
+        let url = self.url()?;
+
+        log::debug!("Creating client with url {} and {:?}", url, self.options);
+
+        let client = Client::builder()
+            .connect_timeout(self.options.connect_timeout)
+            .read_timeout(self.options.read_timeout)
+            .build()
+            .map_err(Error::Build)?;
+
+        Ok(PfoClient {
+            client,
+            url,
+            options: self.options,
+        })
+    }
+
+    /// Build a client with blocking methods, see [`blocking::PfoClient`](crate::blocking::PfoClient).
+    /// [`Self::max_in_flight`] and [`Self::requests_per_second`] do not apply to it, and
+    /// [`Self::read_timeout`] limits each request from connecting to reading all of its response.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(self) -> Result<crate::blocking::PfoClient> {
+        let url = self.url()?;
+
+        log::debug!(
+            "Creating blocking client with url {} and {:?}",
+            url,
+            self.options
+        );
+
+        let client = reqwest::blocking::Client::builder()
+            .connect_timeout(self.options.connect_timeout)
+            .timeout(self.options.read_timeout)
+            .build()
+            .map_err(Error::Build)?;
+
+        Ok(crate::blocking::PfoClient::new(client, url, self.options))
+    }
+
+    fn url(&self) -> Result<Url> {
+        let url = format!("http://{}:{}", self.host, self.port);
+        Url::parse(&url).map_err(|source| Error::InvalidUrl { url, source })
+    }
+}
+
+/// Asynchronous client of the pfo server. Cloning it is cheap, clones share connections.
+#[derive(Clone)]
+pub struct PfoClient {
+    client: Client,
+    url: Url,
+    options: Options,
+}
+
+impl PfoClient {
+    /// Client of the server at `host` and `port` with default options
+    pub fn new(host: impl Into<String>, port: u16) -> Result<Self> {
+        Self::builder().host(host).port(port).build()
+    }
+
+    pub fn builder() -> PfoClientBuilder {
+        PfoClientBuilder::default()
+    }
+
+    /// Await `requests` concurrently, within the in-flight and per second limits of
+    /// [`PfoClientBuilder`]. Results are in the order of `requests`, the first error is returned.
+    pub async fn bulk<T, E, F>(
+        &self,
+        requests: impl IntoIterator<Item = F>,
+    ) -> std::result::Result<Vec<T>, E>
+    where
+        F: Future<Output = std::result::Result<T, E>>,
+    {
+        let limiter = &RateLimiter::new(self.options.requests_per_second);
+
+        stream::iter(requests)
+            .map(|request| async move {
+                limiter.wait().await;
+                request.await
+            })
+            .buffered(self.options.max_in_flight.max(1))
+            .try_collect()
+            .await
+    }
+
+    fn request<B: Serialize>(&self, endpoint: &Endpoint<B>) -> RequestBuilder {
+        let mut request = self
+            .client
+            .request(endpoint.method.clone(), endpoint.url(&self.url));
+
+        if endpoint.should_have_content {
+            request = request.header(ACCEPT, "application/json");
+        }
+
+        if let Some(body) = &endpoint.body {
+            request = request.json(body);
+        }
+
+        request
+    }
+
+    async fn send<B: Serialize>(&self, endpoint: &Endpoint<B>) -> Result<Response> {
+        let (client, request) = self.request(endpoint).build_split();
+        let request = request.map_err(Error::Request)?;
+        let (method, url) = (request.method().clone(), request.url().clone());
+
+        let mut retries = Retries::new(&method, &url, &self.options);
+        let result = loop {
+            let Some(current) = retries.left().then(|| request.try_clone()).flatten() else {
+                break client.execute(request).await;
+            };
+
+            let result = client.execute(current).await;
+            match retries.delay(&result) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => break result,
+            }
+        };
+
+        let response = match result {
+            Ok(response) => response,
+            Err(err) => return Err(Error::from_send(method, url, err)),
+        };
+
+        let status = response.status().as_u16();
+        log::debug!("Got response {}", status);
+        if status >= 400 {
+            return match response.text().await {
+                Ok(body) => Err(Error::from_status(method, url, status, body)),
+                Err(err) => Err(Error::from_send(method, url, err)),
+            };
+        }
+
+        Ok(response)
+    }
+
+    /// Send a request to `endpoint` and decode its JSON response
+    async fn fetch<T: DeserializeOwned>(&self, endpoint: Endpoint) -> Result<T> {
+        self.send(&endpoint)
+            .await?
+            .json()
+            .await
+            .map_err(|source| Error::Decode {
+                what: endpoint.what,
+                source,
+            })
+    }
+
+    /// All portfolios
+    pub async fn list_portfolios(&self) -> Result<Vec<Portfolio>> {
+        self.fetch(Endpoint::list_portfolios()).await
+    }
+
+    /// Portfolio with `id`
+    pub async fn get_portfolio(&self, id: Uuid) -> Result<Portfolio> {
+        self.fetch(Endpoint::get_portfolio(id)).await
+    }
+
+    /// Funds of portfolio `id` with their prices on `date`, otherwise the server decides the date
+    pub async fn get_portfolio_fund_prices(
+        &self,
+        id: Uuid,
+        date: Option<NaiveDate>,
+        sort: Option<Sort>,
+    ) -> Result<Vec<PortfolioFundPrice>> {
+        self.fetch(Endpoint::get_portfolio_fund_prices(id, date, sort))
+            .await
+    }
+
+    /// Latest prices and returns of funds of portfolio `id`
+    pub async fn get_portfolio_fund_price_stats(
+        &self,
+        id: Uuid,
+        sort: Option<Sort>,
+    ) -> Result<Vec<FundPriceStats>> {
+        self.fetch(Endpoint::get_portfolio_fund_price_stats(id, sort))
+            .await
+    }
+
+    /// Amounts of funds of portfolio `id` to buy for `budget`
+    pub async fn get_portfolio_fund_predictions(
+        &self,
+        id: Uuid,
+        budget: f32,
+    ) -> Result<Vec<PortfolioFundPrediction>> {
+        self.fetch(Endpoint::get_portfolio_fund_predictions(id, budget))
+            .await
+    }
+
+    /// Add, change or remove funds of portfolio `id`
+    pub async fn update_portfolio(&self, id: Uuid, update: PortfolioUpdate) -> Result<()> {
+        self.send(&Endpoint::update_portfolio(id, update)).await?;
+
+        Ok(())
+    }
+
+    /// Funds matching `fund_filter`
+    pub async fn get_funds(
+        &self,
+        fund_filter: FundFilter,
+        sort: Option<Sort>,
+    ) -> Result<Vec<FundInfo>> {
+        self.fetch(Endpoint::get_funds(fund_filter, sort)).await
+    }
+
+    /// Latest prices and returns of funds with `codes`, all funds if empty
+    pub async fn get_fund_price_stats(
+        &self,
+        codes: Vec<String>,
+        sort: Option<Sort>,
+    ) -> Result<Vec<FundPriceStats>> {
+        self.fetch(Endpoint::get_fund_price_stats(codes, sort))
+            .await
+    }
+}
+
+/// Spaces out starts of requests evenly, to a given number per second
+struct RateLimiter(Option<Mutex<Interval>>);
+
+impl RateLimiter {
+    fn new(per_second: Option<u32>) -> Self {
+        Self(per_second.filter(|&n| n > 0).map(|n| {
+            let mut interval = tokio::time::interval(Duration::from_secs(1) / n);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            Mutex::new(interval)
+        }))
+    }
+
+    async fn wait(&self) {
+        if let Some(interval) = &self.0 {
+            interval.lock().await.tick().await;
+        }
+    }
+}
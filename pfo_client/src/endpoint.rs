@@ -0,0 +1,128 @@
+use chrono::NaiveDate;
+use reqwest::{Method, Url};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::model::PortfolioUpdate;
+use crate::none_serialize::NoneSerialize;
+use crate::query::{FundFilter, Query, Sort};
+
+/// Request to an endpoint of the server, sent by both the async and the blocking client
+pub(crate) struct Endpoint<B: Serialize = NoneSerialize> {
+    pub method: Method,
+    pub path: String,
+    pub query: Option<Query<'static>>,
+    pub body: Option<B>,
+    /// Whether the response has a JSON body, asked for with an `Accept` header
+    pub should_have_content: bool,
+    /// What the response body is, for decode errors
+    pub what: &'static str,
+}
+
+impl<B: Serialize> Endpoint<B> {
+    /// URL of the endpoint on the server at `base`, with its query
+    pub fn url(&self, base: &Url) -> Url {
+        let mut url = base.clone();
+        url.set_path(&self.path);
+
+        if let Some(query) = &self.query
+            && !query.pairs().is_empty()
+        {
+            url.query_pairs_mut().extend_pairs(query.pairs());
+        }
+
+        log::debug!("Create request for {}", url);
+        url
+    }
+}
+
+impl Endpoint {
+    fn get(path: String, query: Option<Query<'static>>, what: &'static str) -> Self {
+        Self {
+            method: Method::GET,
+            path,
+            query,
+            body: None,
+            should_have_content: true,
+            what,
+        }
+    }
+
+    pub fn list_portfolios() -> Self {
+        Self::get("/p".to_string(), None, "Portfolio list")
+    }
+
+    pub fn get_portfolio(id: Uuid) -> Self {
+        Self::get(format!("/p/{}", id), None, "Portfolio")
+    }
+
+    pub fn get_portfolio_fund_prices(
+        id: Uuid,
+        date: Option<NaiveDate>,
+        sort: Option<Sort>,
+    ) -> Self {
+        let mut query: Query = Vec::with_capacity(3).into();
+        query.push_date("date", date);
+        query.push_sort(sort);
+
+        Self::get(
+            format!("/p/{}/f", id),
+            Some(query),
+            "list of portfolio fund prices",
+        )
+    }
+
+    pub fn get_portfolio_fund_price_stats(id: Uuid, sort: Option<Sort>) -> Self {
+        let mut query: Query = Vec::with_capacity(2).into();
+        query.push_sort(sort);
+
+        Self::get(
+            format!("/p/{}/f/stats", id),
+            Some(query),
+            "list of fund price stats",
+        )
+    }
+
+    pub fn get_portfolio_fund_predictions(id: Uuid, budget: f32) -> Self {
+        let query: Query = vec![("budget", budget.to_string())].into();
+
+        Self::get(
+            format!("/p/{}/f/predictions", id),
+            Some(query),
+            "list of portfolio fund predictions",
+        )
+    }
+
+    pub fn get_funds(fund_filter: FundFilter, sort: Option<Sort>) -> Self {
+        let mut query: Query = Vec::with_capacity(5).into();
+        query.push_sort(sort);
+        query.push_fund_filter(fund_filter);
+
+        Self::get("/f".to_string(), Some(query), "list of fund informations")
+    }
+
+    pub fn get_fund_price_stats(codes: Vec<String>, sort: Option<Sort>) -> Self {
+        let mut query: Query = Vec::with_capacity(3).into();
+        query.push_sort(sort);
+        query.push_vec("codes", codes);
+
+        Self::get(
+            "/f/stats".to_string(),
+            Some(query),
+            "list of fund price stats",
+        )
+    }
+}
+
+impl Endpoint<PortfolioUpdate> {
+    pub fn update_portfolio(id: Uuid, update: PortfolioUpdate) -> Self {
+        Self {
+            method: Method::PUT,
+            path: format!("/p/{}", id),
+            query: None,
+            body: Some(update),
+            should_have_content: false,
+            what: "Portfolio update response",
+        }
+    }
+}
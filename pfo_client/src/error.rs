@@ -0,0 +1,116 @@
+use reqwest::{Method, Url};
+
+use crate::problem_detail::ProblemDetail;
+
+/// Failure of a request sent by [`PfoClient`](crate::PfoClient)
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid URL string: {url}")]
+    InvalidUrl {
+        url: String,
+        #[source]
+        source: url::ParseError,
+    },
+
+    #[error("Failed to create HTTP client")]
+    Build(#[source] reqwest::Error),
+
+    #[error("Failed to build request")]
+    Request(#[source] reqwest::Error),
+
+    #[error("Failed to send {method} request to {url}")]
+    Transport {
+        method: Method,
+        url: Url,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("{method} request to {url} timed out")]
+    Timeout {
+        method: Method,
+        url: Url,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    /// Server responded with a status of 400 or above
+    #[error("{problem}")]
+    Status {
+        method: Method,
+        url: Url,
+        problem: Box<ProblemDetail>,
+    },
+
+    #[error("Failed to decode {what} from response")]
+    Decode {
+        what: &'static str,
+        #[source]
+        source: reqwest::Error,
+    },
+}
+
+/// Result of requests sent by [`PfoClient`](crate::PfoClient)
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Error for a request that could not be completed, telling timeouts apart
+    pub(crate) fn from_send(method: Method, url: Url, source: reqwest::Error) -> Self {
+        if source.is_timeout() {
+            Self::Timeout {
+                method,
+                url,
+                source,
+            }
+        } else {
+            Self::Transport {
+                method,
+                url,
+                source,
+            }
+        }
+    }
+
+    /// Error for a response with a status of 400 or above, whose `body` is usually a ProblemDetail
+    pub(crate) fn from_status(method: Method, url: Url, status: u16, body: String) -> Self {
+        let problem = serde_json::from_str::<ProblemDetail>(&body).unwrap_or_else(|err| {
+            log::debug!("Error response does not contain ProblemDetail: {}", err);
+            ProblemDetail::from_body(status, body)
+        });
+
+        Self::Status {
+            method,
+            url,
+            problem: Box::new(problem),
+        }
+    }
+
+    /// Method of the request that failed, if it was sent
+    pub fn method(&self) -> Option<&Method> {
+        match self {
+            Self::Transport { method, .. }
+            | Self::Timeout { method, .. }
+            | Self::Status { method, .. } => Some(method),
+            _ => None,
+        }
+    }
+
+    /// URL of the request that failed, if it was sent
+    pub fn url(&self) -> Option<&Url> {
+        match self {
+            Self::Transport { url, .. } | Self::Timeout { url, .. } | Self::Status { url, .. } => {
+                Some(url)
+            }
+            Self::Decode { source, .. } => source.url(),
+            _ => None,
+        }
+    }
+
+    /// ProblemDetail of an error response
+    pub fn problem(&self) -> Option<&ProblemDetail> {
+        match self {
+            Self::Status { problem, .. } => Some(problem.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Client of the pfo portfolio server
+//!
+//! [`PfoClient`] sends requests to the endpoints of the server and decodes their responses into the
+//! types of [`model`], which are re-exported at the crate root. Requests that fail return an
+//! [`Error`], carrying the [`ProblemDetail`] of error responses.
+//!
+//! ```no_run
+//! # async fn run() -> pfo_client::Result<()> {
+//! use pfo_client::{PfoClient, Sort};
+//!
+//! let client = PfoClient::new("localhost", 8080)?;
+//! for portfolio in client.list_portfolios().await? {
+//!     let prices = client
+//!         .get_portfolio_fund_prices(portfolio.id, None, Some(Sort::desc("price")))
+//!         .await?;
+//!     println!("{}: {} funds", portfolio.name, prices.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Features
+//!
+//! - `blocking`: [`blocking::PfoClient`] with the same methods, for programs without async. It
+//!   builds requests and handles error responses like [`PfoClient`], without a runtime to manage.
+//! - `table`: table output and clap column enums of model types, used by the `pfo` command
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod client;
+mod endpoint;
+mod error;
+pub mod model;
+mod none_serialize;
+mod problem_detail;
+mod query;
+mod retry;
+
+pub use client::{PfoClient, PfoClientBuilder};
+pub use error::{Error, Result};
+pub use model::*;
+pub use problem_detail::ProblemDetail;
+pub use query::{FundFilter, Sort, SortDirection};
@@ -0,0 +1,37 @@
+use chrono::NaiveDate;
+#[cfg(feature = "table")]
+use clap::ValueEnum;
+#[cfg(feature = "table")]
+use pfo_core::impl_table;
+#[cfg(feature = "table")]
+use pfo_derive::OutputTable;
+use serde::{Deserialize, Serialize};
+
+/// Fund Information
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "table", derive(OutputTable))]
+pub struct FundInfo {
+    #[cfg_attr(feature = "table", column(max_width = 3, is_default))]
+    pub code: String,
+
+    #[cfg_attr(feature = "table", column(max_width = 25, is_default))]
+    pub title: String,
+
+    #[cfg_attr(feature = "table", column(max_width = 25))]
+    pub provider: String,
+
+    #[cfg_attr(feature = "table", column(max_width = 10, is_default))]
+    pub date: NaiveDate,
+
+    #[cfg_attr(
+        feature = "table",
+        column(max_width = 30, is_default, left_align = false)
+    )]
+    pub price: f64,
+
+    #[cfg_attr(feature = "table", column(max_width = 30, left_align = false))]
+    pub total_value: f64,
+}
+
+#[cfg(feature = "table")]
+impl_table!(FundInfo, FundInfoColumn, FundInfoRow);
@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+#[cfg(feature = "table")]
+use clap::ValueEnum;
+#[cfg(feature = "table")]
+use pfo_core::impl_table;
+#[cfg(feature = "table")]
+use pfo_derive::OutputTable;
+use serde::{Deserialize, Serialize};
+
+/// Latest price of a fund with its returns over several periods
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "table", derive(OutputTable))]
+pub struct FundPriceStats {
+    #[cfg_attr(feature = "table", column(max_width = 3, is_default))]
+    pub code: String,
+
+    #[cfg_attr(feature = "table", column(max_width = 10, is_default))]
+    pub date: NaiveDate,
+
+    #[cfg_attr(
+        feature = "table",
+        column(max_width = 30, left_align = false, is_default)
+    )]
+    pub price: f64,
+
+    #[cfg_attr(
+        feature = "table",
+        column(max_width = 30, left_align = false, is_default)
+    )]
+    pub total_value: f64,
+
+    #[cfg_attr(feature = "table", column(max_width = 30, left_align = false))]
+    pub daily_return: Option<f64>,
+
+    #[cfg_attr(
+        feature = "table",
+        column(max_width = 30, left_align = false, is_default)
+    )]
+    pub monthly_return: Option<f64>,
+
+    #[cfg_attr(feature = "table", column(max_width = 30, left_align = false))]
+    pub three_monthly_return: Option<f64>,
+
+    #[cfg_attr(
+        feature = "table",
+        column(max_width = 30, left_align = false, is_default)
+    )]
+    pub six_monthly_return: Option<f64>,
+
+    #[cfg_attr(
+        feature = "table",
+        column(max_width = 30, left_align = false, is_default)
+    )]
+    pub yearly_return: Option<f64>,
+
+    #[cfg_attr(
+        feature = "table",
+        column(max_width = 30, left_align = false, is_default)
+    )]
+    pub three_yearly_return: Option<f64>,
+
+    #[cfg_attr(
+        feature = "table",
+        column(max_width = 30, left_align = false, is_default)
+    )]
+    pub five_yearly_return: Option<f64>,
+}
+
+#[cfg(feature = "table")]
+impl_table!(FundPriceStats, FundPriceStatsColumn, FundPriceStatsRow);
@@ -0,0 +1,27 @@
+//! Types of requests and responses of the pfo server
+
+mod fund_info;
+mod fund_price_stats;
+mod portfolio;
+mod portfolio_fund_prediction;
+mod portfolio_fund_price;
+mod portfolio_fund_update;
+mod portfolio_update;
+
+pub use fund_info::FundInfo;
+#[cfg(feature = "table")]
+pub use fund_info::FundInfoColumn;
+pub use fund_price_stats::FundPriceStats;
+#[cfg(feature = "table")]
+pub use fund_price_stats::FundPriceStatsColumn;
+pub use portfolio::Portfolio;
+#[cfg(feature = "table")]
+pub use portfolio::PortfolioColumn;
+pub use portfolio_fund_prediction::PortfolioFundPrediction;
+#[cfg(feature = "table")]
+pub use portfolio_fund_prediction::PortfolioFundPredictionColumn;
+pub use portfolio_fund_price::PortfolioFundPrice;
+#[cfg(feature = "table")]
+pub use portfolio_fund_price::PortfolioFundPriceColumn;
+pub use portfolio_fund_update::PortfolioFundUpdate;
+pub use portfolio_update::PortfolioUpdate;
@@ -0,0 +1,22 @@
+#[cfg(feature = "table")]
+use clap::ValueEnum;
+#[cfg(feature = "table")]
+use pfo_core::impl_table;
+#[cfg(feature = "table")]
+use pfo_derive::OutputTable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Portfolio listed by `GET /p`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "table", derive(OutputTable))]
+pub struct Portfolio {
+    #[cfg_attr(feature = "table", column(max_width = 36, is_default))]
+    pub id: Uuid,
+
+    #[cfg_attr(feature = "table", column(max_width = 50, is_default))]
+    pub name: String,
+}
+
+#[cfg(feature = "table")]
+impl_table!(Portfolio, PortfolioColumn, PortfolioRow);
@@ -0,0 +1,34 @@
+#[cfg(feature = "table")]
+use clap::ValueEnum;
+#[cfg(feature = "table")]
+use pfo_core::impl_table;
+#[cfg(feature = "table")]
+use pfo_derive::OutputTable;
+use serde::{Deserialize, Serialize};
+
+/// Amount of a fund to buy for a budget, listed by `GET /p/{id}/f/predictions`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "table", derive(OutputTable))]
+pub struct PortfolioFundPrediction {
+    #[cfg_attr(feature = "table", column(max_width = 3, is_default))]
+    pub code: String,
+
+    #[cfg_attr(feature = "table", column(max_width = 25, is_default))]
+    pub title: String,
+
+    #[cfg_attr(feature = "table", column(max_width = 15, is_default))]
+    pub price: f32,
+
+    #[cfg_attr(feature = "table", column(max_width = 10, is_default))]
+    pub amount: u32,
+
+    #[cfg_attr(feature = "table", column(max_width = 15, is_default))]
+    pub weight: f32,
+}
+
+#[cfg(feature = "table")]
+impl_table!(
+    PortfolioFundPrediction,
+    PortfolioFundPredictionColumn,
+    PortfolioFundPredictionRow
+);
@@ -0,0 +1,51 @@
+use chrono::NaiveDate;
+#[cfg(feature = "table")]
+use clap::ValueEnum;
+#[cfg(feature = "table")]
+use pfo_core::impl_table;
+#[cfg(feature = "table")]
+use pfo_derive::OutputTable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Fund of a portfolio with its price on a date, listed by `GET /p/{id}/f`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "table", derive(OutputTable))]
+pub struct PortfolioFundPrice {
+    #[cfg_attr(feature = "table", column(max_width = 36))]
+    pub portfolio_id: Uuid,
+
+    #[cfg_attr(feature = "table", column(max_width = 3, is_default))]
+    pub code: String,
+
+    #[cfg_attr(feature = "table", column(max_width = 25))]
+    pub title: String,
+
+    #[cfg_attr(feature = "table", column(max_width = 10, is_default))]
+    pub date: NaiveDate,
+
+    #[cfg_attr(feature = "table", column(max_width = 30, is_default))]
+    pub price: f64,
+
+    #[cfg_attr(feature = "table", column(max_width = 15))]
+    pub normalized_weight: f32,
+
+    #[cfg_attr(feature = "table", column(max_width = 10))]
+    pub min_amount: u32,
+
+    #[cfg_attr(
+        feature = "table",
+        column(header = "Owned", max_width = 10, is_default)
+    )]
+    pub owned_amount: u32,
+
+    #[cfg_attr(feature = "table", column(max_width = 30, is_default))]
+    pub money_spent: f64,
+}
+
+#[cfg(feature = "table")]
+impl_table!(
+    PortfolioFundPrice,
+    PortfolioFundPriceColumn,
+    PortfolioFundPriceRow
+);
@@ -2,6 +2,8 @@ use std::hash::{Hash, Hasher};
 
 use serde::{Deserialize, Serialize};
 
+/// Fund added to or changed in a portfolio by [`PortfolioUpdate`](crate::PortfolioUpdate), fields
+/// left `None` are not changed. Updates are equal when they are for the same fund.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PortfolioFundUpdate {
     pub fund_code: String,
@@ -2,8 +2,9 @@ use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 
-use crate::portfolio::PortfolioFundUpdate;
+use crate::model::PortfolioFundUpdate;
 
+/// Body of `PUT /p/{id}`, adding or changing funds of a portfolio and removing others
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PortfolioUpdate {
     pub add_codes: HashSet<PortfolioFundUpdate>,
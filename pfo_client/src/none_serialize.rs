@@ -0,0 +1,5 @@
+use serde::Serialize;
+
+/// Body type of requests without a body
+#[derive(Serialize)]
+pub(crate) struct NoneSerialize;
@@ -1,17 +1,32 @@
 use std::fmt::{Debug, Display};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize)]
+/// Body of an error response of the server, as described by RFC 9457
+#[derive(Clone, Deserialize, Serialize)]
 pub struct ProblemDetail {
     #[serde(rename = "type")]
-    _type: Option<String>,
-    title: Option<String>,
-    status: u16,
-    detail: Option<String>,
-    instance: Option<String>,
+    pub problem_type: Option<String>,
+    pub title: Option<String>,
+    pub status: u16,
+    pub detail: Option<String>,
+    pub instance: Option<String>,
     #[serde(rename = "responseBody")]
-    response_body: Option<String>,
+    pub response_body: Option<String>,
+}
+
+impl ProblemDetail {
+    /// Problem for an error response whose body is not a ProblemDetail
+    pub fn from_body(status: u16, body: String) -> Self {
+        Self {
+            problem_type: None,
+            title: None,
+            status,
+            detail: None,
+            instance: None,
+            response_body: (!body.is_empty()).then_some(body),
+        }
+    }
 }
 
 impl Debug for ProblemDetail {
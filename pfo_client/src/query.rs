@@ -0,0 +1,111 @@
+use std::fmt::Display;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Order of items in a response, by one of the columns of the listed item
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Sort {
+    /// Name of the column as known by the server, like `price` or `monthlyReturn`
+    #[serde(rename = "sortBy")]
+    pub by: String,
+
+    #[serde(rename = "sortDirection")]
+    pub direction: SortDirection,
+}
+
+impl Sort {
+    pub fn asc(by: impl Into<String>) -> Self {
+        Self {
+            by: by.into(),
+            direction: SortDirection::Asc,
+        }
+    }
+
+    pub fn desc(by: impl Into<String>) -> Self {
+        Self {
+            by: by.into(),
+            direction: SortDirection::Desc,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl Display for SortDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortDirection::Asc => write!(f, "ASC"),
+            SortDirection::Desc => write!(f, "DESC"),
+        }
+    }
+}
+
+#[cfg(feature = "table")]
+impl<T: pfo_core::output::ColumnEnumSorted> From<pfo_core::sort::SortArguments<T>> for Sort {
+    fn from(sort: pfo_core::sort::SortArguments<T>) -> Self {
+        Self {
+            by: sort.by.to_server_name().to_string(),
+            direction: match sort.dir {
+                pfo_core::sort::SortDirection::Asc => SortDirection::Asc,
+                pfo_core::sort::SortDirection::Desc => SortDirection::Desc,
+            },
+        }
+    }
+}
+
+/// Funds listed by [`PfoClient::get_funds`](crate::PfoClient::get_funds)
+#[derive(Clone, Debug, Default)]
+pub struct FundFilter {
+    /// Date of prices, otherwise the server decides
+    pub date: Option<NaiveDate>,
+    /// Codes of funds to list, all funds if empty
+    pub codes: Vec<String>,
+}
+
+pub(crate) struct Query<'a> {
+    pairs: Vec<(&'a str, String)>,
+}
+
+impl<'a> From<Vec<(&'a str, String)>> for Query<'a> {
+    fn from(pairs: Vec<(&'a str, String)>) -> Self {
+        Query { pairs }
+    }
+}
+
+impl<'a> Query<'a> {
+    pub fn pairs(&self) -> &Vec<(&'a str, String)> {
+        &self.pairs
+    }
+
+    pub fn push_vec(&mut self, key: &'a str, values: Vec<String>) {
+        if !values.is_empty() {
+            self.pairs.push((key, values.join(",")));
+        }
+    }
+
+    pub fn push_date(&mut self, key: &'a str, date: Option<NaiveDate>) {
+        if let Some(date) = date {
+            self.pairs
+                .push((key, format!("{}", date.format("%m.%d.%Y"))));
+        }
+    }
+
+    pub fn push_sort(&mut self, sort: Option<Sort>) {
+        if let Some(sort) = sort {
+            self.pairs.push(("sortBy", sort.by));
+            self.pairs
+                .push(("sortDirection", sort.direction.to_string()));
+        }
+    }
+
+    pub fn push_fund_filter(&mut self, fund_filter: FundFilter) {
+        self.push_date("date", fund_filter.date);
+        self.push_vec("codes", fund_filter.codes);
+    }
+}
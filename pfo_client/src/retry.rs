@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, StatusCode, Url};
+
+use crate::client::Options;
+
+/// Longest delay between retries, also when a `Retry-After` header asks for more
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Statuses after which an idempotent request is sent again
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Delay before retry number `attempt`, counted from 0. It doubles from `base` up to
+/// [`MAX_BACKOFF`] and is jittered down by up to a half, so clients failing together do not retry
+/// together.
+fn backoff(base: Duration, attempt: u32) -> Duration {
+    let delay = base
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF);
+    delay.mul_f64(0.5 + fastrand::f64() * 0.5)
+}
+
+/// Delay requested by a `Retry-After` header, given either in seconds or as an HTTP date, up to
+/// [`MAX_BACKOFF`]
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    let delay = match value.parse() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value).ok()?;
+            (date.with_timezone(&Utc) - Utc::now())
+                .to_std()
+                .unwrap_or_default()
+        }
+    };
+
+    Some(delay.min(MAX_BACKOFF))
+}
+
+/// Status and headers of a response of either the async or the blocking client
+pub(crate) trait ResponseMeta {
+    fn status(&self) -> StatusCode;
+    fn headers(&self) -> &HeaderMap;
+}
+
+impl ResponseMeta for reqwest::Response {
+    fn status(&self) -> StatusCode {
+        self.status()
+    }
+
+    fn headers(&self) -> &HeaderMap {
+        self.headers()
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl ResponseMeta for reqwest::blocking::Response {
+    fn status(&self) -> StatusCode {
+        self.status()
+    }
+
+    fn headers(&self) -> &HeaderMap {
+        self.headers()
+    }
+}
+
+/// Retries left for one request
+pub(crate) struct Retries<'a> {
+    method: &'a Method,
+    url: &'a Url,
+    retries: u32,
+    backoff: Duration,
+    attempt: u32,
+}
+
+impl<'a> Retries<'a> {
+    pub fn new(method: &'a Method, url: &'a Url, options: &Options) -> Self {
+        Self {
+            method,
+            url,
+            // Only GET requests are idempotent, others are sent once
+            retries: if method == Method::GET {
+                options.retries
+            } else {
+                0
+            },
+            backoff: options.backoff,
+            attempt: 0,
+        }
+    }
+
+    pub fn left(&self) -> bool {
+        self.attempt < self.retries
+    }
+
+    /// Delay before sending the request again after `result`, `None` if `result` is final
+    pub fn delay<R: ResponseMeta>(
+        &mut self,
+        result: &Result<R, reqwest::Error>,
+    ) -> Option<Duration> {
+        let (reason, delay) = match result {
+            Err(err) if err.is_connect() => (
+                "connection error".to_string(),
+                backoff(self.backoff, self.attempt),
+            ),
+            Ok(response) if is_retryable_status(response.status()) => (
+                response.status().to_string(),
+                retry_after(response.headers())
+                    .unwrap_or_else(|| backoff(self.backoff, self.attempt)),
+            ),
+            _ => return None,
+        };
+
+        self.attempt += 1;
+        log::warn!(
+            "{} {} failed with {}, retrying in {:.1}s ({}/{})",
+            self.method,
+            self.url,
+            reason,
+            delay.as_secs_f64(),
+            self.attempt,
+            self.retries
+        );
+        Some(delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        HeaderMap::from_iter([(RETRY_AFTER, HeaderValue::from_str(retry_after).unwrap())])
+    }
+
+    #[test]
+    fn backoff_doubles_within_jitter() {
+        let base = Duration::from_millis(500);
+
+        for attempt in 0..4 {
+            let full = base * 2u32.pow(attempt);
+            let delay = backoff(base, attempt);
+            assert!(
+                delay >= full / 2 && delay <= full,
+                "{:?} {:?}",
+                attempt,
+                delay
+            );
+        }
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        for attempt in [6, 32, u32::MAX] {
+            let delay = backoff(Duration::from_secs(1), attempt);
+            assert!(
+                delay >= MAX_BACKOFF / 2 && delay <= MAX_BACKOFF,
+                "{:?}",
+                delay
+            );
+        }
+    }
+
+    #[test]
+    fn retry_after_parses_seconds() {
+        assert_eq!(retry_after(&headers("7")), Some(Duration::from_secs(7)));
+        assert_eq!(retry_after(&headers(" 0 ")), Some(Duration::ZERO));
+        assert_eq!(retry_after(&headers("3600")), Some(MAX_BACKOFF));
+    }
+
+    #[test]
+    fn retry_after_parses_http_dates() {
+        let date = |seconds| (Utc::now() + chrono::Duration::seconds(seconds)).to_rfc2822();
+
+        let delay = retry_after(&headers(&date(10))).unwrap();
+        assert!(delay > Duration::from_secs(8) && delay <= Duration::from_secs(10));
+        assert_eq!(retry_after(&headers(&date(-10))), Some(Duration::ZERO));
+        assert_eq!(retry_after(&headers(&date(3600))), Some(MAX_BACKOFF));
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn retry_after_ignores_missing_and_invalid_headers() {
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+        assert_eq!(retry_after(&headers("soon")), None);
+        assert_eq!(retry_after(&headers("-5")), None);
+    }
+}
@@ -0,0 +1,96 @@
+//! Tests of the client against the mock server with its bundled fixtures
+
+use chrono::NaiveDate;
+use pfo_client::{Error, FundFilter, PfoClient, Sort};
+use pfo_mock::{Fixtures, MockServer};
+use uuid::Uuid;
+
+const MAIN: Uuid = Uuid::from_u128(0x11111111_1111_1111_1111_111111111111);
+
+fn client(server: &MockServer) -> PfoClient {
+    PfoClient::builder()
+        .host("127.0.0.1")
+        .port(server.port())
+        .retries(0)
+        .build()
+        .expect("client is built")
+}
+
+#[tokio::test]
+async fn lists_portfolios() {
+    let server = MockServer::start(Fixtures::bundled()).unwrap();
+
+    let portfolios = client(&server).list_portfolios().await.unwrap();
+
+    let names: Vec<_> = portfolios.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Main", "Second"]);
+}
+
+#[tokio::test]
+async fn sorts_and_filters_by_date() {
+    let server = MockServer::start(Fixtures::bundled()).unwrap();
+    let date = NaiveDate::from_ymd_opt(2024, 2, 1);
+
+    let prices = client(&server)
+        .get_portfolio_fund_prices(MAIN, date, Some(Sort::asc("price")))
+        .await
+        .unwrap();
+
+    let prices: Vec<_> = prices.iter().map(|p| (p.code.as_str(), p.price)).collect();
+    assert_eq!(prices, [("BBB", 5.2), ("AAA", 9.5)]);
+}
+
+#[tokio::test]
+async fn error_response_has_problem_detail() {
+    let server = MockServer::start(Fixtures::bundled()).unwrap();
+
+    let err = client(&server)
+        .get_funds(FundFilter::default(), Some(Sort::desc("unknown")))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::Status { .. }));
+    let problem = err.problem().unwrap();
+    assert_eq!(problem.status, 400);
+    assert_eq!(problem.detail.as_deref(), Some("Cannot sort by unknown"));
+    assert_eq!(problem.instance.as_deref(), Some("/f"));
+}
+
+#[tokio::test]
+async fn bulk_keeps_order_of_requests() {
+    let server = MockServer::start(Fixtures::bundled()).unwrap();
+    let client = client(&server);
+    let codes = ["CCC", "AAA", "BBB"];
+
+    let stats = client
+        .bulk(
+            codes
+                .iter()
+                .map(|code| client.get_fund_price_stats(vec![code.to_string()], None)),
+        )
+        .await
+        .unwrap();
+
+    let listed: Vec<_> = stats.iter().map(|s| s[0].code.as_str()).collect();
+    assert_eq!(listed, codes);
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn blocking_client_matches_async_client() {
+    let server = MockServer::start(Fixtures::bundled()).unwrap();
+    let client = PfoClient::builder()
+        .host("127.0.0.1")
+        .port(server.port())
+        .build_blocking()
+        .unwrap();
+
+    let stats = client
+        .get_portfolio_fund_price_stats(MAIN, Some(Sort::desc("monthlyReturn")))
+        .unwrap();
+    let err = client.get_portfolio(Uuid::nil()).unwrap_err();
+
+    let codes: Vec<_> = stats.iter().map(|s| s.code.as_str()).collect();
+    assert_eq!(codes, ["AAA", "BBB"]);
+    assert_eq!(err.problem().map(|p| p.status), Some(404));
+}
@@ -1,4 +1,5 @@
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
+use clap::builder::ArgExt;
 use clap::{Args, ValueEnum};
 use uuid::Uuid;
 
@@ -31,6 +32,38 @@ pub trait ColumnEnumSorted: ColumnEnum {
     fn to_server_name(&self) -> &str;
 }
 
+/// Column of a table as listed in generated documentation
+#[derive(Clone, Debug)]
+pub struct ColumnDoc {
+    pub name: String,
+    pub header: String,
+    pub is_default: bool,
+}
+
+/// Columns of a [`ColumnEnum`], attached to the arguments selecting them so documentation
+/// generated from the command tree can list them
+#[derive(Clone, Debug)]
+pub struct ColumnsDoc(pub Vec<ColumnDoc>);
+
+impl ArgExt for ColumnsDoc {}
+
+impl ColumnsDoc {
+    pub fn of<T: ColumnEnum>() -> Self {
+        Self(
+            T::value_variants()
+                .iter()
+                .filter_map(|v| {
+                    Some(ColumnDoc {
+                        name: v.to_possible_value()?.get_name().to_string(),
+                        header: v.header().to_string(),
+                        is_default: v.is_default(),
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
 pub trait RowStruct {
     type ColumnEnum: ColumnEnum;
     type Target: Table;
@@ -54,12 +87,24 @@ impl ToRowValue for String {
     }
 }
 
+impl ToRowValue for bool {
+    fn to_row_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 impl ToRowValue for u32 {
     fn to_row_value(&self) -> String {
         self.to_string()
     }
 }
 
+impl ToRowValue for i64 {
+    fn to_row_value(&self) -> String {
+        self.to_string()
+    }
+}
+
 impl ToRowValue for f32 {
     fn to_row_value(&self) -> String {
         format!("{:.2}", self)
@@ -78,6 +123,12 @@ impl ToRowValue for NaiveDate {
     }
 }
 
+impl ToRowValue for NaiveDateTime {
+    fn to_row_value(&self) -> String {
+        self.format("%m.%d.%Y %H:%M:%S").to_string()
+    }
+}
+
 impl ToRowValue for Uuid {
     fn to_row_value(&self) -> String {
         self.to_string()
@@ -93,12 +144,51 @@ impl<T: ToRowValue> ToRowValue for Option<T> {
     }
 }
 
+/// Print rows of a table whose columns are only known at runtime, aligned like derived tables
+pub fn print_dynamic_table(
+    headers: &[String],
+    left_align: &[bool],
+    rows: &[Vec<String>],
+    no_headers: bool,
+) {
+    const COLUMN_SPACING: usize = 4;
+
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain((!no_headers).then(|| headers[i].len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let lines = (!no_headers)
+        .then_some(headers)
+        .into_iter()
+        .chain(rows.iter().map(Vec::as_slice));
+    for line in lines {
+        for (i, value) in line.iter().enumerate() {
+            if left_align[i] {
+                print!("{:<width$}", value, width = widths[i]);
+            } else {
+                print!("{:>width$}", value, width = widths[i]);
+            }
+
+            print!("{}", " ".repeat(COLUMN_SPACING));
+        }
+
+        println!();
+    }
+}
+
 #[derive(Args)]
 pub struct TableArgs<T: Clone + ColumnEnum + Send + Sync + 'static> {
     #[arg(
         short = 'o',
         long = "output",
         value_delimiter = ',',
+        add = ColumnsDoc::of::<T>(),
         help = "Limit output to only these columns"
     )]
     pub columns: Option<Vec<T>>,
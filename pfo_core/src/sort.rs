@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use clap::ValueEnum;
+use clap::builder::ArgExt;
 use serde::Serialize;
 
 use crate::output::ColumnEnumSorted;
@@ -34,6 +35,23 @@ impl SortDirection {
     }
 }
 
+/// Sort keys and directions accepted by [`SortArguments`], attached to the arguments parsing them
+/// so documentation generated from the command tree can list them
+#[derive(Clone, Debug)]
+pub struct SortDoc {
+    pub keys: Vec<String>,
+    pub directions: Vec<String>,
+}
+
+impl ArgExt for SortDoc {}
+
+fn value_names<T: ValueEnum>() -> Vec<String> {
+    T::value_variants()
+        .iter()
+        .filter_map(|v| Some(v.to_possible_value()?.get_name().to_string()))
+        .collect()
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct SortArguments<T: ColumnEnumSorted> {
     #[serde(rename = "sortBy")]
@@ -53,6 +71,13 @@ impl<T: ColumnEnumSorted> SortArguments<T> {
         })
     }
 
+    pub fn doc() -> SortDoc {
+        SortDoc {
+            keys: value_names::<T>(),
+            directions: value_names::<SortDirection>(),
+        }
+    }
+
     pub fn get_help() -> String {
         format!(
             "<by> <direction> [, <by> <direction>]\nBY: {}\nDIRECTION: {}",
@@ -0,0 +1,109 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use pfo_client::{FundInfo, FundPriceStats, Portfolio};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Fund of a portfolio, priced from [`Fixtures::funds`] when listed
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PortfolioFundFixture {
+    pub code: String,
+    pub weight: u32,
+    pub min_amount: u32,
+    pub owned_amount: u32,
+    pub money_spent: f64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PortfolioFixture {
+    pub id: Uuid,
+    pub name: String,
+    pub funds: Vec<PortfolioFundFixture>,
+}
+
+impl PortfolioFixture {
+    pub fn portfolio(&self) -> Portfolio {
+        Portfolio {
+            id: self.id,
+            name: self.name.clone(),
+        }
+    }
+}
+
+/// Data served by the mock server, read from `portfolios.json`, `funds.json` and
+/// `fund_stats.json` of a directory
+#[derive(Clone, Debug)]
+pub struct Fixtures {
+    pub portfolios: Vec<PortfolioFixture>,
+    /// Prices of funds, one for each fund and date
+    pub funds: Vec<FundInfo>,
+    pub fund_stats: Vec<FundPriceStats>,
+}
+
+fn parse<T: DeserializeOwned>(name: &str, json: &str) -> Result<T> {
+    serde_json::from_str(json).context(format!("Invalid fixture file {}", name))
+}
+
+impl Fixtures {
+    /// Fixtures of `dir`
+    pub fn load(dir: &Path) -> Result<Self> {
+        let read = |name: &str| {
+            let path = dir.join(name);
+            fs::read_to_string(&path)
+                .context(format!("Failed to read fixture file {}", path.display()))
+        };
+
+        Ok(Self {
+            portfolios: parse("portfolios.json", &read("portfolios.json")?)?,
+            funds: parse("funds.json", &read("funds.json")?)?,
+            fund_stats: parse("fund_stats.json", &read("fund_stats.json")?)?,
+        })
+    }
+
+    /// Fixtures in the `fixtures` directory of this crate: portfolios `Main` and `Second` holding
+    /// funds `AAA`, `BBB` and `CCC`, priced on 02.01.2024 and 03.01.2024
+    pub fn bundled() -> Self {
+        Self {
+            portfolios: parse(
+                "portfolios.json",
+                include_str!("../fixtures/portfolios.json"),
+            )
+            .expect("bundled fixture is valid"),
+            funds: parse("funds.json", include_str!("../fixtures/funds.json"))
+                .expect("bundled fixture is valid"),
+            fund_stats: parse(
+                "fund_stats.json",
+                include_str!("../fixtures/fund_stats.json"),
+            )
+            .expect("bundled fixture is valid"),
+        }
+    }
+
+    pub fn portfolio(&self, id: Uuid) -> Option<&PortfolioFixture> {
+        self.portfolios.iter().find(|p| p.id == id)
+    }
+
+    pub fn portfolio_mut(&mut self, id: Uuid) -> Option<&mut PortfolioFixture> {
+        self.portfolios.iter_mut().find(|p| p.id == id)
+    }
+
+    /// Latest price of fund `code` on or before `date`, latest of all if `date` is `None`
+    pub fn fund(&self, code: &str, date: Option<NaiveDate>) -> Option<&FundInfo> {
+        self.funds
+            .iter()
+            .filter(|f| f.code == code && date.is_none_or(|date| f.date <= date))
+            .max_by_key(|f| f.date)
+    }
+
+    /// Codes of all funds, sorted
+    pub fn fund_codes(&self) -> Vec<&str> {
+        let mut codes: Vec<&str> = self.funds.iter().map(|f| f.code.as_str()).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        codes
+    }
+}
@@ -0,0 +1,20 @@
+//! Mock of the pfo server, answering from fixture files
+//!
+//! It implements the endpoints used by `pfo_client` with sorting by `sortBy` and `sortDirection`,
+//! the `date` and `codes` filters, and ProblemDetail error responses. [`MockServer`] runs it on a
+//! free port for tests, the `pfo-mock-server` binary serves it for demos.
+//!
+//! ```no_run
+//! use pfo_mock::{Fixtures, MockServer};
+//!
+//! let server = MockServer::start(Fixtures::bundled()).unwrap();
+//! println!("pfo --port {} portfolio list", server.port());
+//! ```
+
+mod fixtures;
+mod problem;
+mod server;
+mod sort;
+
+pub use fixtures::{Fixtures, PortfolioFixture, PortfolioFundFixture};
+pub use server::{MockServer, router, serve};
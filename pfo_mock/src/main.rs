@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use pfo_mock::Fixtures;
+use tokio::net::TcpListener;
+
+#[derive(Parser)]
+#[command(
+    name = "pfo-mock-server",
+    about = "Serve a mock pfo server from fixture files"
+)]
+struct Args {
+    #[arg(
+        short = 'H',
+        long,
+        default_value = "127.0.0.1",
+        help = "Address to listen on"
+    )]
+    host: String,
+
+    #[arg(short, long, default_value_t = 8080, help = "Port to listen on")]
+    port: u16,
+
+    #[arg(
+        short,
+        long,
+        value_name = "DIR",
+        help = "Directory with portfolios.json, funds.json and fund_stats.json. Bundled fixtures are served if omitted"
+    )]
+    fixtures: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let fixtures = match &args.fixtures {
+        Some(dir) => Fixtures::load(dir)?,
+        None => Fixtures::bundled(),
+    };
+
+    let listener = TcpListener::bind((args.host.as_str(), args.port))
+        .await
+        .context(format!("Failed to listen on {}:{}", args.host, args.port))?;
+    println!(
+        "Serving mock pfo server on http://{}",
+        listener.local_addr()?
+    );
+
+    pfo_mock::serve(listener, fixtures)
+        .await
+        .context("Mock server failed")
+}
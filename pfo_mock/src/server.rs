@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::{StatusCode, Uri};
+use axum::routing::get;
+use axum::{Json, Router, middleware};
+use chrono::NaiveDate;
+use pfo_client::{
+    FundInfo, FundPriceStats, Portfolio, PortfolioFundPrediction, PortfolioFundPrice,
+    PortfolioUpdate,
+};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use crate::fixtures::{Fixtures, PortfolioFixture, PortfolioFundFixture};
+use crate::problem::{Problem, with_instance};
+use crate::sort::sort;
+
+type Fixture = Arc<Mutex<Fixtures>>;
+type Params = Query<HashMap<String, String>>;
+type Reply<T> = Result<Json<T>, Problem>;
+
+/// Routes of the pfo server, answered from `fixtures`. Portfolio updates change the fixtures in
+/// memory only.
+pub fn router(fixtures: Fixtures) -> Router {
+    Router::new()
+        .route("/p", get(list_portfolios))
+        .route("/p/{id}", get(get_portfolio).put(update_portfolio))
+        .route("/p/{id}/f", get(get_portfolio_fund_prices))
+        .route("/p/{id}/f/stats", get(get_portfolio_fund_price_stats))
+        .route("/p/{id}/f/predictions", get(get_portfolio_fund_predictions))
+        .route("/f", get(get_funds))
+        .route("/f/stats", get(get_fund_price_stats))
+        .fallback(
+            |uri: Uri| async move { Problem::not_found(format!("No endpoint {}", uri.path())) },
+        )
+        .layer(middleware::from_fn(with_instance))
+        .with_state(Arc::new(Mutex::new(fixtures)))
+}
+
+/// Serve `fixtures` on `listener` until the returned future is dropped
+pub async fn serve(listener: TcpListener, fixtures: Fixtures) -> io::Result<()> {
+    axum::serve(listener, router(fixtures)).await
+}
+
+/// Mock server running on a thread of its own, stopped when dropped
+pub struct MockServer {
+    addr: SocketAddr,
+    stop: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl MockServer {
+    /// Serve `fixtures` on a free port of 127.0.0.1
+    pub fn start(fixtures: Fixtures) -> io::Result<Self> {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0))?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let (stop, stopped) = oneshot::channel();
+        let thread = std::thread::spawn(move || {
+            runtime.block_on(async move {
+                let listener = TcpListener::from_std(listener)?;
+                tokio::select! {
+                    result = serve(listener, fixtures) => result,
+                    _ = stopped => Ok(()),
+                }
+            })
+        });
+
+        Ok(Self {
+            addr,
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn parse_id(id: &str) -> Result<Uuid, Problem> {
+    Uuid::parse_str(id).map_err(|_| Problem::bad_request(format!("Invalid portfolio id {}", id)))
+}
+
+fn parse_date(params: &HashMap<String, String>) -> Result<Option<NaiveDate>, Problem> {
+    params
+        .get("date")
+        .map(|date| {
+            NaiveDate::parse_from_str(date, "%m.%d.%Y").map_err(|_| {
+                Problem::bad_request(format!("Invalid date {}, expected MM.DD.YYYY", date))
+            })
+        })
+        .transpose()
+}
+
+fn parse_codes(params: &HashMap<String, String>) -> Vec<String> {
+    params
+        .get("codes")
+        .map(|codes| codes.split(',').map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Run `f` with the portfolio `id` of `fixtures`, or respond with 404
+fn with_portfolio<T>(
+    fixtures: &Fixture,
+    id: &str,
+    f: impl FnOnce(&Fixtures, &PortfolioFixture) -> Result<T, Problem>,
+) -> Result<T, Problem> {
+    let id = parse_id(id)?;
+    let fixtures = fixtures.lock().expect("fixtures are not poisoned");
+    let portfolio = fixtures
+        .portfolio(id)
+        .ok_or_else(|| Problem::not_found(format!("Portfolio {} not found", id)))?;
+    f(&fixtures, portfolio)
+}
+
+/// Funds of `portfolio` with their latest price on or before `date`, funds without a price are
+/// left out
+fn fund_prices(
+    fixtures: &Fixtures,
+    portfolio: &PortfolioFixture,
+    date: Option<NaiveDate>,
+) -> Vec<PortfolioFundPrice> {
+    let total_weight: u32 = portfolio.funds.iter().map(|f| f.weight).sum();
+
+    portfolio
+        .funds
+        .iter()
+        .filter_map(|fund| {
+            let info = fixtures.fund(&fund.code, date)?;
+            Some(PortfolioFundPrice {
+                portfolio_id: portfolio.id,
+                code: fund.code.clone(),
+                title: info.title.clone(),
+                date: info.date,
+                price: info.price,
+                normalized_weight: if total_weight > 0 {
+                    fund.weight as f32 / total_weight as f32
+                } else {
+                    0.0
+                },
+                min_amount: fund.min_amount,
+                owned_amount: fund.owned_amount,
+                money_spent: fund.money_spent,
+            })
+        })
+        .collect()
+}
+
+async fn list_portfolios(State(fixtures): State<Fixture>) -> Json<Vec<Portfolio>> {
+    let fixtures = fixtures.lock().expect("fixtures are not poisoned");
+    Json(
+        fixtures
+            .portfolios
+            .iter()
+            .map(PortfolioFixture::portfolio)
+            .collect(),
+    )
+}
+
+async fn get_portfolio(
+    State(fixtures): State<Fixture>,
+    Path(id): Path<String>,
+) -> Reply<Portfolio> {
+    with_portfolio(&fixtures, &id, |_, portfolio| {
+        Ok(Json(portfolio.portfolio()))
+    })
+}
+
+async fn get_portfolio_fund_prices(
+    State(fixtures): State<Fixture>,
+    Path(id): Path<String>,
+    Query(params): Params,
+) -> Reply<Vec<PortfolioFundPrice>> {
+    let date = parse_date(&params)?;
+    with_portfolio(&fixtures, &id, |fixtures, portfolio| {
+        sort(fund_prices(fixtures, portfolio, date), &params).map(Json)
+    })
+}
+
+async fn get_portfolio_fund_price_stats(
+    State(fixtures): State<Fixture>,
+    Path(id): Path<String>,
+    Query(params): Params,
+) -> Reply<Vec<FundPriceStats>> {
+    with_portfolio(&fixtures, &id, |fixtures, portfolio| {
+        let stats = fixtures
+            .fund_stats
+            .iter()
+            .filter(|s| portfolio.funds.iter().any(|f| f.code == s.code))
+            .cloned()
+            .collect();
+        sort(stats, &params).map(Json)
+    })
+}
+
+/// Spends `budget` on each fund in proportion to its normalized weight
+async fn get_portfolio_fund_predictions(
+    State(fixtures): State<Fixture>,
+    Path(id): Path<String>,
+    Query(params): Params,
+) -> Reply<Vec<PortfolioFundPrediction>> {
+    let budget: f64 = params
+        .get("budget")
+        .ok_or_else(|| Problem::bad_request("Query parameter budget is required"))?
+        .parse()
+        .map_err(|_| Problem::bad_request("Budget must be a number"))?;
+    if budget < 0.0 {
+        return Err(Problem::bad_request("Budget must not be negative"));
+    }
+
+    with_portfolio(&fixtures, &id, |fixtures, portfolio| {
+        let predictions = fund_prices(fixtures, portfolio, None)
+            .into_iter()
+            .map(|price| PortfolioFundPrediction {
+                amount: (budget * price.normalized_weight as f64 / price.price).floor() as u32,
+                code: price.code,
+                title: price.title,
+                price: price.price as f32,
+                weight: price.normalized_weight,
+            })
+            .collect();
+        Ok(Json(predictions))
+    })
+}
+
+async fn update_portfolio(
+    State(fixtures): State<Fixture>,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> Result<StatusCode, Problem> {
+    let id = parse_id(&id)?;
+    let update: PortfolioUpdate = serde_json::from_slice(&body)
+        .map_err(|err| Problem::bad_request(format!("Invalid portfolio update: {}", err)))?;
+
+    let mut fixtures = fixtures.lock().expect("fixtures are not poisoned");
+    if let Some(fund) = update
+        .add_codes
+        .iter()
+        .find(|f| fixtures.fund(&f.fund_code, None).is_none())
+    {
+        return Err(Problem::bad_request(format!(
+            "Fund {} not found",
+            fund.fund_code
+        )));
+    }
+
+    let portfolio = fixtures
+        .portfolio_mut(id)
+        .ok_or_else(|| Problem::not_found(format!("Portfolio {} not found", id)))?;
+    portfolio
+        .funds
+        .retain(|f| !update.remove_codes.contains(&f.code));
+
+    for add in update.add_codes {
+        let index = match portfolio.funds.iter().position(|f| f.code == add.fund_code) {
+            Some(index) => index,
+            None => {
+                portfolio.funds.push(PortfolioFundFixture {
+                    code: add.fund_code.clone(),
+                    weight: 1,
+                    min_amount: 0,
+                    owned_amount: 0,
+                    money_spent: 0.0,
+                });
+                portfolio.funds.len() - 1
+            }
+        };
+
+        let fund = &mut portfolio.funds[index];
+        fund.weight = add.weight.unwrap_or(fund.weight);
+        fund.min_amount = add.min_amount.unwrap_or(fund.min_amount);
+        fund.owned_amount = add.owned_amount.unwrap_or(fund.owned_amount);
+        fund.money_spent = add.total_money_spent.unwrap_or(fund.money_spent);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Latest price of each fund on or before `date`, only funds in `codes` if given
+async fn get_funds(State(fixtures): State<Fixture>, Query(params): Params) -> Reply<Vec<FundInfo>> {
+    let date = parse_date(&params)?;
+    let codes = parse_codes(&params);
+
+    let fixtures = fixtures.lock().expect("fixtures are not poisoned");
+    let funds = fixtures
+        .fund_codes()
+        .into_iter()
+        .filter(|code| codes.is_empty() || codes.iter().any(|c| c == code))
+        .filter_map(|code| fixtures.fund(code, date).cloned())
+        .collect();
+    sort(funds, &params).map(Json)
+}
+
+async fn get_fund_price_stats(
+    State(fixtures): State<Fixture>,
+    Query(params): Params,
+) -> Reply<Vec<FundPriceStats>> {
+    let codes = parse_codes(&params);
+
+    let fixtures = fixtures.lock().expect("fixtures are not poisoned");
+    let stats = fixtures
+        .fund_stats
+        .iter()
+        .filter(|s| codes.is_empty() || codes.contains(&s.code))
+        .cloned()
+        .collect();
+    sort(stats, &params).map(Json)
+}
@@ -0,0 +1,79 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::problem::Problem;
+
+/// `lowerCamelCase` sort key sent by clients to the `snake_case` field it sorts by
+fn field_name(sort_by: &str) -> String {
+    let mut name = String::with_capacity(sort_by.len() + 4);
+    for c in sort_by.chars() {
+        if c.is_ascii_uppercase() {
+            name.push('_');
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    name
+}
+
+/// Nulls first, then numbers and strings in their natural order
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Less,
+        (_, Value::Null) => Ordering::Greater,
+        _ => Ordering::Equal,
+    }
+}
+
+/// Sort `items` by the `sortBy` and `sortDirection` query parameters, keeping their order if
+/// `sortBy` is not given
+pub fn sort<T: Serialize>(
+    items: Vec<T>,
+    params: &HashMap<String, String>,
+) -> Result<Vec<T>, Problem> {
+    let Some(sort_by) = params.get("sortBy") else {
+        return Ok(items);
+    };
+
+    let descending = match params.get("sortDirection").map(|d| d.to_ascii_uppercase()) {
+        None => false,
+        Some(d) if d == "ASC" => false,
+        Some(d) if d == "DESC" => true,
+        Some(d) => {
+            return Err(Problem::bad_request(format!(
+                "Invalid sort direction {}",
+                d
+            )));
+        }
+    };
+
+    let field = field_name(sort_by);
+    let mut keyed = items
+        .into_iter()
+        .map(|item| {
+            let key = serde_json::to_value(&item)
+                .ok()
+                .and_then(|value| value.get(&field).cloned())
+                .ok_or_else(|| Problem::bad_request(format!("Cannot sort by {}", sort_by)))?;
+            Ok((key, item))
+        })
+        .collect::<Result<Vec<_>, Problem>>()?;
+
+    keyed.sort_by(|(a, _), (b, _)| {
+        if descending {
+            compare(b, a)
+        } else {
+            compare(a, b)
+        }
+    });
+
+    Ok(keyed.into_iter().map(|(_, item)| item).collect())
+}